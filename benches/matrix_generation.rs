@@ -0,0 +1,83 @@
+//! Compares the dependency matrix generation with the occurrence index against
+//! checking every activity pair on every trace, for each log in `event-logs/`.
+//!
+//! Run with `cargo bench --bench matrix_generation`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use matrix_classifier::dependency_types::{
+    existential::check_existential_dependency, temporal::check_temporal_dependency,
+};
+use matrix_classifier::{
+    generate_indexed_dependency_matrix, parse_into_traces, InputSource, MatrixOptions,
+};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+/// Checks every ordered pair of distinct activities on every trace, like the matrix
+/// generation did before the occurrence index.
+fn pairwise_matrix(traces: &[Vec<&str>], threshold: f64) -> usize {
+    let activities: BTreeSet<&str> = traces.iter().flatten().copied().collect();
+    let mut dependencies = 0;
+    for &from in &activities {
+        for &to in &activities {
+            if from == to {
+                continue;
+            }
+            let temporal = check_temporal_dependency(from, to, traces, threshold);
+            let existential = check_existential_dependency(from, to, traces, threshold);
+            dependencies += usize::from(temporal.is_some()) + usize::from(existential.is_some());
+        }
+    }
+    dependencies
+}
+
+fn event_logs() -> Vec<(String, Vec<Vec<String>>)> {
+    let directory = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("event-logs");
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(&directory)
+        .expect("event-logs directory")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "xes"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+            let traces = parse_into_traces(InputSource::path(&path)).expect("valid event log");
+            (name, traces)
+        })
+        .collect()
+}
+
+fn bench_matrix_generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("matrix_generation");
+    group.sample_size(10);
+
+    for (name, traces) in event_logs() {
+        let traces_str: Vec<Vec<&str>> = traces
+            .iter()
+            .map(|trace| trace.iter().map(String::as_str).collect())
+            .collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("pairwise", &name),
+            &traces_str,
+            |b, traces| b.iter(|| pairwise_matrix(black_box(traces), 1.0)),
+        );
+        group.bench_with_input(BenchmarkId::new("indexed", &name), &traces, |b, traces| {
+            b.iter(|| {
+                generate_indexed_dependency_matrix(
+                    black_box(traces),
+                    1.0,
+                    1.0,
+                    &MatrixOptions::default(),
+                )
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_matrix_generation);
+criterion_main!(benches);
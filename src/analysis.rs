@@ -0,0 +1,450 @@
+//! Reproducible classification runs.
+//!
+//! An [`AnalysisConfig`] holds every setting that influences a classification: the
+//! parser options, the thresholds, the matrix and classification options and the
+//! version of the classification rules. [`analyze`] runs the whole pipeline on it and
+//! returns every result together with a [`Manifest`], from which the run can be
+//! repeated exactly (see [`AnalysisConfig::from_json`]).
+
+use crate::classification::{
+    classify_matrix_with_options, ClassificationOptions, ClassificationOutput, RULE_SET_VERSION,
+};
+use crate::dependency_matrix::DependencyMatrix;
+use crate::error::Error;
+use crate::event_log::{EventLog, ParseReport, TiePolicy};
+use crate::input::{InputDigest, InputSource, LogFormat};
+use crate::matrix_generation::{
+    generate_indexed_dependency_matrix_with_thresholds,
+    generate_indexed_event_log_dependency_matrix_with_thresholds,
+    generate_indexed_interval_dependency_matrix_with_thresholds, MatrixOptions, Thresholds,
+};
+use crate::parser::{
+    parse_csv_event_log, parse_event_log, parse_lifecycle_event_log, parse_mxml_event_log,
+    parse_ocel_event_logs, parse_xes_variants, CsvImportOptions, ParseOptions,
+};
+use serde::{Deserialize, Serialize};
+
+/// All settings of a classification run.
+///
+/// Missing fields take their default when read from JSON, so a configuration only
+/// needs to list what differs from the defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnalysisConfig {
+    pub thresholds: Thresholds,
+    /// Options of XES logs.
+    pub parse: ParseOptions,
+    /// Options of CSV logs.
+    pub csv: CsvImportOptions,
+    /// Pair the start/complete transitions of the events into activity instances and
+    /// compute the temporal dependencies on their intervals.
+    pub lifecycle_intervals: bool,
+    /// How events of a case with identical timestamps are ordered.
+    pub tie_policy: TiePolicy,
+    /// Read XES logs trace by trace, keeping only the trace variants in memory. Gives
+    /// the same result as reading the whole log.
+    pub streaming: bool,
+    /// Object type to flatten OCEL logs on; `None` classifies every object type.
+    pub object_type: Option<String>,
+    pub matrix: MatrixOptions,
+    pub classification: ClassificationOptions,
+    /// Version of the classification rules the configuration was made for, see
+    /// [`RULE_SET_VERSION`].
+    pub rule_set_version: u32,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            thresholds: Thresholds::default(),
+            parse: ParseOptions::default(),
+            csv: CsvImportOptions::default(),
+            lifecycle_intervals: false,
+            tie_policy: TiePolicy::DocumentOrder,
+            streaming: false,
+            object_type: None,
+            matrix: MatrixOptions::default(),
+            classification: ClassificationOptions::default(),
+            rule_set_version: RULE_SET_VERSION,
+        }
+    }
+}
+
+impl AnalysisConfig {
+    /// Checks the thresholds, the option combinations and that the configuration was
+    /// made for the rules of this build.
+    pub fn validate(&self) -> Result<(), Error> {
+        self.thresholds.validate()?;
+        if self.rule_set_version != RULE_SET_VERSION {
+            return Err(Error::InvalidConfig(format!(
+                "rule set version {} is not supported, this build implements version {}",
+                self.rule_set_version, RULE_SET_VERSION
+            )));
+        }
+        if self.lifecycle_intervals && self.streaming {
+            return Err(Error::InvalidConfig(
+                "lifecycle intervals cannot be computed on a streamed log".to_string(),
+            ));
+        }
+        if self.tie_policy != TiePolicy::DocumentOrder && self.streaming {
+            return Err(Error::InvalidConfig(
+                "a streamed log keeps events with equal timestamps in document order".to_string(),
+            ));
+        }
+        if self.tie_policy != TiePolicy::DocumentOrder && self.lifecycle_intervals {
+            return Err(Error::InvalidConfig(
+                "lifecycle intervals with equal timestamps are always concurrent".to_string(),
+            ));
+        }
+        if self.tie_policy == TiePolicy::Concurrent && self.parse.keep_events_without_timestamp {
+            return Err(Error::InvalidConfig(
+                "events without timestamp cannot be concurrent to other events".to_string(),
+            ));
+        }
+        if !self.csv.delimiter.is_ascii() {
+            return Err(Error::InvalidConfig(
+                "CSV delimiter must be a single ASCII character".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Reads and validates a configuration from JSON.
+    ///
+    /// Besides a configuration itself, this accepts a [`Manifest`] or a list of
+    /// manifests (as written for object-centric logs) and takes the configuration they
+    /// record, which repeats that run.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let invalid = |e: serde_json::Error| Error::InvalidConfig(e.to_string());
+        let mut value: serde_json::Value = serde_json::from_str(json).map_err(invalid)?;
+        if let Some(manifests) = value.as_array_mut() {
+            value = manifests
+                .first_mut()
+                .ok_or_else(|| Error::InvalidConfig("the list of manifests is empty".to_string()))?
+                .take();
+        }
+        if let Some(config) = value.get_mut("config") {
+            value = config.take();
+        }
+        let config: Self = serde_json::from_value(value).map_err(invalid)?;
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+/// Record of how a result was computed: the input, the crate version and the complete
+/// configuration, next to the result itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Version of this crate.
+    pub version: String,
+    /// Name of the classified file (`"-"` for stdin).
+    pub input: String,
+    /// SHA-256 digest (lower-case hex) of the input, after decompression.
+    pub input_sha256: String,
+    /// The format the input was parsed as.
+    pub format: LogFormat,
+    /// The object type the OCEL log was flattened on, if any.
+    pub object_type: Option<String>,
+    pub config: AnalysisConfig,
+    /// The timestamp ties of the parsed log, unless it was streamed.
+    pub parse_report: Option<ParseReport>,
+    pub output: ClassificationOutput,
+}
+
+impl Manifest {
+    /// Pretty-printed JSON of the manifest.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("manifests are always serializable")
+    }
+}
+
+/// The result of classifying a log, or one object type of an OCEL log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    pub object_type: Option<String>,
+    pub parse_report: Option<ParseReport>,
+    pub matrix: DependencyMatrix,
+    pub output: ClassificationOutput,
+    pub manifest: Manifest,
+}
+
+/// Parses, classifies and records the log `file_name` read from `source`.
+///
+/// The log format is detected from `file_name` and, for `.xml` files, the root element
+/// (see [`LogFormat::detect`]).
+/// OCEL logs give one [`Analysis`] per object type, all other logs exactly one.
+pub fn analyze(
+    file_name: &str,
+    source: InputSource,
+    config: &AnalysisConfig,
+) -> Result<Vec<Analysis>, Error> {
+    config.validate()?;
+    let thresholds = &config.thresholds;
+    let matrix_options = &config.matrix;
+    // Counts the ties before applying the tie policy to the event log
+    let event_log_matrix = |mut event_log: EventLog| {
+        let report = event_log.parse_report();
+        event_log.order_ties(&config.tie_policy);
+        let matrix = match config.tie_policy {
+            _ if config.lifecycle_intervals => generate_indexed_interval_dependency_matrix_with_thresholds(
+                &event_log.activity_instances(),
+                thresholds,
+                matrix_options,
+            ),
+            TiePolicy::Concurrent => generate_indexed_interval_dependency_matrix_with_thresholds(
+                &event_log.activity_instants(),
+                thresholds,
+                matrix_options,
+            ),
+            _ => generate_indexed_event_log_dependency_matrix_with_thresholds(&event_log, thresholds, matrix_options),
+        };
+        (Some(report), matrix)
+    };
+
+    let digest = InputDigest::default();
+    let (format, source) = LogFormat::detect(file_name, digest.source(source)?)?;
+    let matrices = match format {
+        LogFormat::Ocel => parse_ocel_event_logs(source, config.object_type.as_deref())?
+            .into_iter()
+            .map(|(object_type, event_log)| (Some(object_type), event_log_matrix(event_log)))
+            .collect(),
+        LogFormat::Mxml => vec![(None, event_log_matrix(parse_mxml_event_log(source)?))],
+        LogFormat::Csv => vec![(None, event_log_matrix(parse_csv_event_log(source, &config.csv)?))],
+        LogFormat::Xes => {
+            let report_and_matrix = if config.lifecycle_intervals {
+                event_log_matrix(parse_lifecycle_event_log(source, &config.parse)?)
+            } else if config.streaming {
+                let variant_log = parse_xes_variants(source, &config.parse)?;
+                let matrix =
+                    generate_indexed_dependency_matrix_with_thresholds(&variant_log, thresholds, matrix_options);
+                (None, matrix)
+            } else {
+                event_log_matrix(parse_event_log(source, &config.parse)?)
+            };
+            vec![(None, report_and_matrix)]
+        }
+    };
+
+    let input_sha256 = digest.finish();
+
+    Ok(matrices
+        .into_iter()
+        .map(|(object_type, (parse_report, matrix))| {
+            let output = classify_matrix_with_options(&matrix, &config.classification);
+            let manifest = Manifest {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                input: file_name.to_string(),
+                input_sha256: input_sha256.clone(),
+                format,
+                object_type: object_type.clone(),
+                config: config.clone(),
+                parse_report,
+                output: output.clone(),
+            };
+            Analysis {
+                object_type,
+                parse_report,
+                matrix,
+                output,
+                manifest,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    const LOG: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<log xes.version="1.0">
+  <trace>
+    <string key="concept:name" value="1"/>
+    <event><string key="concept:name" value="A"/><date key="time:timestamp" value="2024-01-01T00:00:00Z"/></event>
+    <event><string key="concept:name" value="B"/><date key="time:timestamp" value="2024-01-01T01:00:00Z"/></event>
+  </trace>
+  <trace>
+    <string key="concept:name" value="2"/>
+    <event><string key="concept:name" value="A"/><date key="time:timestamp" value="2024-01-01T00:00:00Z"/></event>
+    <event><string key="concept:name" value="C"/><date key="time:timestamp" value="2024-01-01T01:00:00Z"/></event>
+  </trace>
+</log>"#;
+
+    #[test]
+    fn test_analyze_records_manifest() {
+        let config = AnalysisConfig {
+            thresholds: Thresholds::uniform(0.5, 0.8),
+            ..Default::default()
+        };
+        let analyses = analyze("log.xes", InputSource::Bytes(LOG.as_bytes()), &config).unwrap();
+        assert_eq!(analyses.len(), 1);
+
+        let analysis = &analyses[0];
+        assert_eq!(analysis.manifest.input, "log.xes");
+        assert_eq!(analysis.manifest.format, LogFormat::Xes);
+        assert_eq!(
+            analysis.manifest.input_sha256,
+            "f9eb23cc8d97b67008ea936686cf345ca8a384e7ef3d7e9f0f2abb48f6335b79"
+        );
+        assert_eq!(analysis.manifest.config, config);
+        assert_eq!(analysis.manifest.output, analysis.output);
+
+        // The manifest repeats the run: same configuration, same result
+        let reread = AnalysisConfig::from_json(&analysis.manifest.to_json()).unwrap();
+        assert_eq!(reread, config);
+        let repeated = analyze("log.xes", InputSource::Bytes(LOG.as_bytes()), &reread).unwrap();
+        assert_eq!(repeated[0].matrix, analysis.matrix);
+        assert_eq!(repeated[0].output, analysis.output);
+
+        // The digest is taken after decompression
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(LOG.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let analyses = analyze("log.xes.gz", InputSource::Bytes(&compressed), &config).unwrap();
+        assert_eq!(analyses[0].manifest.input_sha256, analysis.manifest.input_sha256);
+    }
+
+    #[test]
+    fn test_analyze_concurrent_ties() {
+        let config = AnalysisConfig {
+            tie_policy: TiePolicy::Concurrent,
+            ..Default::default()
+        };
+        let analyses = analyze("log.xes", InputSource::Bytes(LOG.as_bytes()), &config).unwrap();
+        let statistics = analyses[0]
+            .matrix
+            .statistics_by_name("A", "B")
+            .copied()
+            .unwrap();
+        assert_eq!(
+            statistics.time_gaps.map(|time_gaps| time_gaps.median),
+            Some(chrono::Duration::hours(1))
+        );
+
+        let streaming = AnalysisConfig {
+            streaming: true,
+            ..config
+        };
+        assert!(matches!(
+            streaming.validate(),
+            Err(Error::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_analyze_tie_policies() {
+        // B and C share a timestamp, C comes first in the log and B first by resource
+        let log = r#"<log xes.version="1.0">
+  <trace>
+    <event><string key="concept:name" value="A"/><date key="time:timestamp" value="2024-01-01T00:00:00Z"/></event>
+    <event><string key="concept:name" value="C"/><string key="org:resource" value="Carol"/><date key="time:timestamp" value="2024-01-02T00:00:00Z"/></event>
+    <event><string key="concept:name" value="B"/><string key="org:resource" value="Bob"/><date key="time:timestamp" value="2024-01-02T00:00:00Z"/></event>
+  </trace>
+</log>"#;
+        let temporal = |tie_policy| {
+            let config = AnalysisConfig {
+                tie_policy,
+                ..Default::default()
+            };
+            let analysis = analyze("log.xes", InputSource::Bytes(log.as_bytes()), &config)
+                .unwrap()
+                .remove(0);
+            assert_eq!(analysis.parse_report.unwrap().tied_events, 2);
+            assert_eq!(analysis.manifest.parse_report, analysis.parse_report);
+            analysis.matrix.get_by_name("B", "C").unwrap().temporal
+        };
+
+        use crate::dependency_types::temporal::{DependencyType, Direction};
+        assert_eq!(
+            temporal(TiePolicy::DocumentOrder),
+            Some((DependencyType::Direct, Direction::Backward))
+        );
+        assert_eq!(
+            temporal(TiePolicy::Attribute("org:resource".to_string())),
+            Some((DependencyType::Direct, Direction::Forward))
+        );
+        assert_eq!(temporal(TiePolicy::Concurrent), None);
+    }
+
+    #[test]
+    fn test_tie_policies_are_rejected_with_lifecycle_intervals() {
+        for tie_policy in [
+            TiePolicy::Concurrent,
+            TiePolicy::Attribute("org:resource".to_string()),
+        ] {
+            let config = AnalysisConfig {
+                lifecycle_intervals: true,
+                tie_policy,
+                ..Default::default()
+            };
+            assert!(matches!(config.validate(), Err(Error::InvalidConfig(_))));
+        }
+
+        let config = AnalysisConfig {
+            lifecycle_intervals: true,
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_analyze_records_time_gaps_in_document_order() {
+        let analyses =
+            analyze("log.xes", InputSource::Bytes(LOG.as_bytes()), &AnalysisConfig::default()).unwrap();
+        let statistics = analyses[0]
+            .matrix
+            .statistics_by_name("A", "B")
+            .copied()
+            .unwrap();
+        assert_eq!(
+            statistics.time_gaps.map(|time_gaps| (time_gaps.median, time_gaps.count)),
+            Some((chrono::Duration::hours(1), 1))
+        );
+        assert_eq!(
+            analyses[0]
+                .matrix
+                .statistics_by_name("B", "C")
+                .copied()
+                .and_then(|statistics| statistics.time_gaps),
+            None
+        );
+    }
+
+    #[test]
+    fn test_concurrent_ties_are_rejected_with_untimed_events() {
+        let mut config = AnalysisConfig {
+            tie_policy: TiePolicy::Concurrent,
+            ..Default::default()
+        };
+        config.parse.keep_events_without_timestamp = true;
+        assert!(matches!(config.validate(), Err(Error::InvalidConfig(_))));
+
+        config.tie_policy = TiePolicy::DocumentOrder;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_config_from_json() {
+        let config =
+            AnalysisConfig::from_json(r#"{"thresholds": {"existential": {"nand": 0.9}}}"#).unwrap();
+        assert_eq!(config.thresholds.existential.nand, 0.9);
+        assert_eq!(config.thresholds.existential.implication, 1.0);
+        assert_eq!(config.rule_set_version, RULE_SET_VERSION);
+
+        assert!(matches!(
+            AnalysisConfig::from_json(r#"{"thresholds": {"temporal": {"direct": 1.5}}}"#),
+            Err(Error::InvalidThreshold { name: "Direct", .. })
+        ));
+        assert!(matches!(
+            AnalysisConfig::from_json(r#"{"rule_set_version": 0}"#),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            AnalysisConfig::from_json(r#"{"treshold": 0.5}"#),
+            Err(Error::InvalidConfig(_))
+        ));
+    }
+}
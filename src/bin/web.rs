@@ -0,0 +1,1079 @@
+use matrix_classifier::{
+    analyze, list_classifiers, ocel_object_types, parse_ocel, AnalysisConfig, ClassificationOptions,
+    ClassificationOutput, CsvImportOptions, Error, EventLogClassifier, ExistentialThresholds, InputSource,
+    LogFormat, Manifest, MatrixOptions, ParseError, ParseOptions, TemporalThresholds, Thresholds,
+    TiePolicy, TypeAggregation, RULE_SET_VERSION,
+};
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{HtmlInputElement, HtmlSelectElement, MouseEvent, ProgressEvent, FileReader, Event, InputEvent};
+use yew::prelude::*;
+
+#[derive(Debug, thiserror::Error, Clone, PartialEq)]
+enum AppError {
+    #[error("File reading error: {0}")]
+    FileRead(String),
+    #[error("Parsing error: {0}")]
+    Parse(String),
+    #[error("CSV parsing error: {0}")]
+    CsvParse(String),
+    #[error("OCEL parsing error: {0}")]
+    OcelParse(String),
+    #[error("The event log contains no events")]
+    EmptyLog,
+    #[error("{0}")]
+    InvalidThreshold(String),
+    #[error("{0}")]
+    InvalidConfig(String),
+}
+
+impl From<Error> for AppError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Io(e) => AppError::FileRead(e.to_string()),
+            Error::Parse(ParseError::Csv(e)) => AppError::CsvParse(e.to_string()),
+            Error::Parse(e @ (ParseError::OcelJson(_)
+            | ParseError::InvalidOcel(_)
+            | ParseError::UnknownObjectType(_))) => {
+                AppError::OcelParse(e.to_string())
+            }
+            Error::Parse(e) => AppError::Parse(e.to_string()),
+            Error::EmptyLog => AppError::EmptyLog,
+            e @ Error::InvalidThreshold { .. } => AppError::InvalidThreshold(e.to_string()),
+            e @ Error::InvalidConfig(_) => AppError::InvalidConfig(e.to_string()),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum XesField {
+    ActivityKeys,
+    TimestampKey,
+}
+
+/// Threshold of one relation, overriding the existential or temporal threshold.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum ThresholdField {
+    Implication,
+    Equivalence,
+    NegatedEquivalence,
+    Nand,
+    Or,
+    Repetition,
+    Direct,
+    Eventual,
+    Parallel,
+}
+
+impl ThresholdField {
+    const ALL: [ThresholdField; 9] = [
+        ThresholdField::Implication,
+        ThresholdField::Equivalence,
+        ThresholdField::NegatedEquivalence,
+        ThresholdField::Nand,
+        ThresholdField::Or,
+        ThresholdField::Repetition,
+        ThresholdField::Direct,
+        ThresholdField::Eventual,
+        ThresholdField::Parallel,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            ThresholdField::Implication => "Implication:",
+            ThresholdField::Equivalence => "Equivalence:",
+            ThresholdField::NegatedEquivalence => "Negated Equivalence:",
+            ThresholdField::Nand => "NAND:",
+            ThresholdField::Or => "OR:",
+            ThresholdField::Repetition => "Repetition:",
+            ThresholdField::Direct => "Direct:",
+            ThresholdField::Eventual => "Eventual:",
+            ThresholdField::Parallel => "Parallel:",
+        }
+    }
+
+    fn id(self) -> &'static str {
+        match self {
+            ThresholdField::Implication => "implication-threshold",
+            ThresholdField::Equivalence => "equivalence-threshold",
+            ThresholdField::NegatedEquivalence => "negated-equivalence-threshold",
+            ThresholdField::Nand => "nand-threshold",
+            ThresholdField::Or => "or-threshold",
+            ThresholdField::Repetition => "repetition-threshold",
+            ThresholdField::Direct => "direct-threshold",
+            ThresholdField::Eventual => "eventual-threshold",
+            ThresholdField::Parallel => "parallel-threshold",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum CsvField {
+    CaseColumn,
+    ActivityColumn,
+    TimestampColumn,
+    LifecycleColumn,
+    Delimiter,
+    TimestampFormat,
+}
+
+enum AppMessage {
+    FileSelected(Option<String>),
+    FileLoaded { file_name: String, content: Result<Vec<u8>, String> },
+    ExistentialThresholdChanged(String),
+    TemporalThresholdChanged(String),
+    ThresholdOverrideChanged(ThresholdField, String),
+    TypeAggregationChanged(String),
+    DirectRatioChanged(String),
+    XesOptionChanged(XesField, String),
+    KeepEventsWithoutTimestampChanged(bool),
+    ClassifierChanged(Option<String>),
+    LifecycleIntervalsChanged(bool),
+    TiePolicyChanged(String),
+    TieAttributeChanged(String),
+    SelfLoopsChanged(bool),
+    CsvOptionChanged(CsvField, String),
+    ObjectTypeChanged(Option<String>),
+    ProcessLog,
+    SetClassificationResult(Result<Vec<ClassifiedLog>, AppError>),
+}
+
+/// Classification of the uploaded log, or of one object type of an OCEL log.
+#[derive(Clone, PartialEq)]
+struct ClassifiedLog {
+    object_type: Option<String>,
+    output: ClassificationOutput,
+    manifest: Manifest,
+    matrix_csv: String, // Dependency matrix with statistics and time gaps, for download
+}
+
+#[derive(Clone, PartialEq)]
+struct AppState {
+    file_name: Option<String>,
+    file_content: Option<Vec<u8>>, // Raw bytes, .xes.gz uploads are decompressed when parsed
+    file_format: LogFormat, // By the file name, and the root element of .xml files once loaded
+    existential_threshold_str: String, // Store as String
+    temporal_threshold_str: String,    // Store as String
+    threshold_override_strs: HashMap<ThresholdField, String>, // Empty or missing uses the threshold above
+    type_aggregation_str: String, // "any-eventual", "majority" or "direct-ratio"
+    direct_ratio_str: String,     // Ratio of the "direct-ratio" aggregation
+    parse_options: ParseOptions,
+    activity_keys_str: String, // Space separated, like the keys of an XES classifier
+    available_classifiers: Vec<EventLogClassifier>, // Declared in the header of the loaded XES log
+    lifecycle_intervals: bool,
+    tie_policy_str: String,     // "document-order", "concurrent" or "attribute"
+    tie_attribute_str: String, // Event attribute of the "attribute" tie policy
+    include_self_loops: bool,
+    csv_options: CsvImportOptions,
+    csv_delimiter_str: String,
+    ocel_object_types: Vec<String>, // Declared in the loaded OCEL log
+    ocel_object_type: Option<String>, // None classifies every object type
+    classification_result: Option<Result<Vec<ClassifiedLog>, AppError>>,
+    is_processing: bool,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self {
+            file_name: None,
+            file_content: None,
+            file_format: LogFormat::Xes,
+            existential_threshold_str: "1.0".to_string(), // Default to "1.0" string
+            temporal_threshold_str: "1.0".to_string(),    // Default to "1.0" string
+            threshold_override_strs: HashMap::new(),
+            type_aggregation_str: TypeAggregation::AnyEventual.to_string(),
+            direct_ratio_str: "0.9".to_string(),
+            parse_options: ParseOptions::default(),
+            activity_keys_str: ParseOptions::default().activity_keys.join(" "),
+            available_classifiers: Vec::new(),
+            lifecycle_intervals: false,
+            tie_policy_str: TiePolicy::DocumentOrder.to_string(),
+            tie_attribute_str: "org:resource".to_string(),
+            include_self_loops: false,
+            csv_options: CsvImportOptions::default(),
+            csv_delimiter_str: ",".to_string(),
+            ocel_object_types: Vec::new(),
+            ocel_object_type: None,
+            classification_result: None,
+            is_processing: false,
+        }
+    }
+}
+
+impl AppState {
+    /// Applies a message to the state.
+    fn update(&mut self, msg: AppMessage) {
+        match msg {
+            AppMessage::FileSelected(file_name_opt) => {
+                if let Some(file_name) = file_name_opt {
+                    self.file_format = LogFormat::from_file_name(&file_name);
+                    self.file_name = Some(file_name);
+                    self.file_content = None;
+                    self.classification_result = None;
+                    self.available_classifiers = Vec::new();
+                    self.parse_options.classifier = None;
+                    self.ocel_object_types = Vec::new();
+                    self.ocel_object_type = None;
+                } else {
+                    self.file_name = None;
+                    self.file_content = None;
+                    self.file_format = LogFormat::Xes;
+                    self.classification_result = None;
+                }
+            }
+            AppMessage::FileLoaded { file_name, content } => {
+                // Ignore the content of a file that is no longer selected
+                if self.file_name.as_ref() != Some(&file_name) {
+                    return;
+                }
+                match content {
+                    Ok(content) => {
+                        if let Ok((format, _)) = LogFormat::detect(&file_name, InputSource::Bytes(&content)) {
+                            self.file_format = format;
+                        }
+                        match self.file_format {
+                            LogFormat::Xes => {
+                                // Logs without (valid) header simply offer no classifiers
+                                self.available_classifiers =
+                                    list_classifiers(InputSource::Bytes(&content)).unwrap_or_default();
+                            }
+                            LogFormat::Ocel => {
+                                self.ocel_object_types = parse_ocel(InputSource::Bytes(&content))
+                                    .map(|ocel| ocel_object_types(&ocel).into_iter().map(str::to_string).collect())
+                                    .unwrap_or_default();
+                            }
+                            LogFormat::Csv | LogFormat::Mxml => {}
+                        }
+                        self.file_content = Some(content);
+                    }
+                    Err(e) => {
+                        self.classification_result =
+                            Some(Err(AppError::FileRead(e)));
+                    }
+                }
+                self.is_processing = false;
+            }
+            AppMessage::ExistentialThresholdChanged(val_str) => {
+                self.existential_threshold_str = val_str;
+            }
+            AppMessage::TemporalThresholdChanged(val_str) => {
+                self.temporal_threshold_str = val_str;
+            }
+            AppMessage::ThresholdOverrideChanged(field, val_str) => {
+                self.threshold_override_strs.insert(field, val_str);
+            }
+            AppMessage::TypeAggregationChanged(kind) => {
+                self.type_aggregation_str = kind;
+            }
+            AppMessage::DirectRatioChanged(val_str) => {
+                self.direct_ratio_str = val_str;
+            }
+            AppMessage::XesOptionChanged(field, value) => match field {
+                XesField::ActivityKeys => {
+                    self.parse_options.activity_keys = parse_activity_keys_str(&value);
+                    self.activity_keys_str = value;
+                }
+                XesField::TimestampKey => self.parse_options.timestamp_key = value,
+            },
+            AppMessage::ClassifierChanged(classifier) => {
+                self.parse_options.classifier = classifier;
+            }
+            AppMessage::LifecycleIntervalsChanged(lifecycle_intervals) => {
+                self.lifecycle_intervals = lifecycle_intervals;
+            }
+            AppMessage::TiePolicyChanged(kind) => {
+                self.tie_policy_str = kind;
+            }
+            AppMessage::TieAttributeChanged(attribute) => {
+                self.tie_attribute_str = attribute;
+            }
+            AppMessage::SelfLoopsChanged(include_self_loops) => {
+                self.include_self_loops = include_self_loops;
+            }
+            AppMessage::KeepEventsWithoutTimestampChanged(keep) => {
+                self.parse_options.keep_events_without_timestamp = keep;
+            }
+            AppMessage::CsvOptionChanged(field, value) => {
+                let options = &mut self.csv_options;
+                match field {
+                    CsvField::CaseColumn => options.case_column = value,
+                    CsvField::ActivityColumn => options.activity_column = value,
+                    CsvField::TimestampColumn => options.timestamp_column = value,
+                    CsvField::LifecycleColumn => {
+                        options.lifecycle_column = Some(value).filter(|c| !c.is_empty());
+                    }
+                    CsvField::Delimiter => {
+                        if let Some(delimiter) = parse_delimiter_str(&value) {
+                            options.delimiter = delimiter;
+                        }
+                        self.csv_delimiter_str = value;
+                    }
+                    CsvField::TimestampFormat => {
+                        options.timestamp_format = Some(value).filter(|f| !f.is_empty());
+                    }
+                }
+            }
+            AppMessage::ObjectTypeChanged(object_type) => {
+                self.ocel_object_type = object_type;
+            }
+            AppMessage::ProcessLog => {
+                self.is_processing = true;
+                self.classification_result = None;
+            }
+            AppMessage::SetClassificationResult(result) => {
+                self.classification_result = Some(result);
+                self.is_processing = false;
+            }
+        }
+    }
+}
+
+impl Reducible for AppState {
+    type Action = AppMessage;
+
+    fn reduce(self: Rc<Self>, msg: AppMessage) -> Rc<Self> {
+        let mut new_state = (*self).clone();
+        new_state.update(msg);
+        Rc::new(new_state)
+    }
+}
+
+fn parse_threshold_str(s: &str) -> Option<f64> {
+    s.parse::<f64>().ok().filter(|&val| (0.0..=1.0).contains(&val))
+}
+
+/// `Some(None)` for an empty input (no override), `None` for an invalid one.
+fn parse_threshold_override_str(s: &str) -> Option<Option<f64>> {
+    if s.trim().is_empty() {
+        Some(None)
+    } else {
+        parse_threshold_str(s).map(Some)
+    }
+}
+
+fn parse_type_aggregation(kind: &str, direct_ratio: &str) -> Option<TypeAggregation> {
+    match kind {
+        "direct-ratio" => parse_threshold_str(direct_ratio).map(TypeAggregation::DirectRatio),
+        kind => kind.parse().ok(),
+    }
+}
+
+fn parse_tie_policy(kind: &str, attribute: &str) -> Option<TiePolicy> {
+    match kind {
+        "attribute" => Some(attribute.trim()).filter(|key| !key.is_empty()).map(|key| TiePolicy::Attribute(key.to_string())),
+        kind => kind.parse().ok(),
+    }
+}
+
+fn relation_thresholds(
+    overrides: &HashMap<ThresholdField, String>,
+    existential_threshold: f64,
+    temporal_threshold: f64,
+    type_aggregation: TypeAggregation,
+) -> Option<Thresholds> {
+    let threshold_override = |field| parse_threshold_override_str(overrides.get(&field).map_or("", String::as_str));
+    let threshold = |field, default| threshold_override(field).map(|value| value.unwrap_or(default));
+    Some(Thresholds {
+        existential: ExistentialThresholds {
+            implication: threshold(ThresholdField::Implication, existential_threshold)?,
+            equivalence: threshold(ThresholdField::Equivalence, existential_threshold)?,
+            negated_equivalence: threshold(ThresholdField::NegatedEquivalence, existential_threshold)?,
+            nand: threshold(ThresholdField::Nand, existential_threshold)?,
+            or: threshold(ThresholdField::Or, existential_threshold)?,
+            repetition: threshold(ThresholdField::Repetition, existential_threshold)?,
+        },
+        temporal: TemporalThresholds {
+            direct: threshold(ThresholdField::Direct, temporal_threshold)?,
+            eventual: threshold(ThresholdField::Eventual, temporal_threshold)?,
+            parallel: threshold_override(ThresholdField::Parallel)?, // Parallel relations only if set
+            type_aggregation,
+        },
+    })
+}
+
+fn parse_delimiter_str(s: &str) -> Option<u8> {
+    match s.as_bytes() {
+        [delimiter] => Some(*delimiter),
+        _ => None,
+    }
+}
+
+/// File extensions of the logs the library reads, see [`LogFormat::from_file_name`].
+const LOG_EXTENSIONS: [&str; 7] = ["xes", "mxml", "csv", "json", "jsonocel", "xml", "xmlocel"];
+
+/// The `accept` list of the file input: every log extension, plain and gzip-compressed.
+fn accepted_file_types() -> String {
+    LOG_EXTENSIONS
+        .iter()
+        .flat_map(|extension| [format!(".{}", extension), format!(".{}.gz", extension)])
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn parse_activity_keys_str(s: &str) -> Vec<String> {
+    s.split_whitespace().map(str::to_string).collect()
+}
+
+#[function_component(App)]
+fn app() -> Html {
+    let app_state_handle: UseReducerHandle<AppState> = use_reducer(AppState::default);
+
+    let dispatch = {
+        let app_state_handle = app_state_handle.clone();
+        Rc::new(move |msg: AppMessage| app_state_handle.dispatch(msg))
+    };
+
+    let on_file_change = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Some(files) = input.files() {
+                if let Some(file) = files.get(0) {
+                    let file_name = file.name();
+                    dispatch(AppMessage::FileSelected(Some(file_name.clone())));
+
+                    let reader = FileReader::new().unwrap();
+                    let dispatch_clone = dispatch.clone();
+                    let onload = Closure::wrap(Box::new(move |e: ProgressEvent| {
+                        let reader: FileReader = e.target().unwrap().dyn_into().unwrap();
+                        let content = js_sys::Uint8Array::new(&reader.result().unwrap()).to_vec();
+                        dispatch_clone(AppMessage::FileLoaded { file_name: file_name.clone(), content: Ok(content) });
+                    }) as Box<dyn FnMut(_)>);
+
+                    reader.set_onload(Some(onload.as_ref().unchecked_ref()));
+                    reader.read_as_array_buffer(&file).unwrap();
+                    onload.forget(); // Prevent closure from being dropped
+                } else {
+                    dispatch(AppMessage::FileSelected(None));
+                }
+            }
+        })
+    };
+
+    let on_existential_threshold_change = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            dispatch(AppMessage::ExistentialThresholdChanged(input.value()));
+        })
+    };
+
+    let on_temporal_threshold_change = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            dispatch(AppMessage::TemporalThresholdChanged(input.value()));
+        })
+    };
+
+    let on_xes_option_change = |field: XesField| {
+        let dispatch = dispatch.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            dispatch(AppMessage::XesOptionChanged(field, input.value()));
+        })
+    };
+
+    let on_keep_events_without_timestamp_change = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            dispatch(AppMessage::KeepEventsWithoutTimestampChanged(input.checked()));
+        })
+    };
+
+    let on_lifecycle_intervals_change = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            dispatch(AppMessage::LifecycleIntervalsChanged(input.checked()));
+        })
+    };
+
+    let on_tie_policy_change = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            dispatch(AppMessage::TiePolicyChanged(select.value()));
+        })
+    };
+
+    let on_tie_attribute_change = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            dispatch(AppMessage::TieAttributeChanged(input.value()));
+        })
+    };
+
+    let on_self_loops_change = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            dispatch(AppMessage::SelfLoopsChanged(input.checked()));
+        })
+    };
+
+    let on_classifier_change = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let value = select.value();
+            dispatch(AppMessage::ClassifierChanged(Some(value).filter(|v| !v.is_empty())));
+        })
+    };
+
+    let on_object_type_change = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let value = select.value();
+            dispatch(AppMessage::ObjectTypeChanged(Some(value).filter(|v| !v.is_empty())));
+        })
+    };
+
+    let on_threshold_override_change = |field: ThresholdField| {
+        let dispatch = dispatch.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            dispatch(AppMessage::ThresholdOverrideChanged(field, input.value()));
+        })
+    };
+
+    let on_type_aggregation_change = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            dispatch(AppMessage::TypeAggregationChanged(select.value()));
+        })
+    };
+
+    let on_direct_ratio_change = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            dispatch(AppMessage::DirectRatioChanged(input.value()));
+        })
+    };
+
+    let on_csv_option_change = |field: CsvField| {
+        let dispatch = dispatch.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            dispatch(AppMessage::CsvOptionChanged(field, input.value()));
+        })
+    };
+
+    let on_process_log = {
+        let app_state_snapshot = (*app_state_handle).clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |_mouse_event: MouseEvent| {
+            // Parse and validate thresholds at the point of processing
+            let temp_thresh_opt = parse_threshold_str(&app_state_snapshot.temporal_threshold_str);
+            let ex_thresh_opt = parse_threshold_str(&app_state_snapshot.existential_threshold_str);
+            let type_aggregation_opt = parse_type_aggregation(
+                &app_state_snapshot.type_aggregation_str,
+                &app_state_snapshot.direct_ratio_str,
+            );
+            let thresholds_opt = temp_thresh_opt.zip(ex_thresh_opt).zip(type_aggregation_opt).and_then(
+                |((temp_thresh_val, ex_thresh_val), type_aggregation)| {
+                    relation_thresholds(
+                        &app_state_snapshot.threshold_override_strs,
+                        ex_thresh_val,
+                        temp_thresh_val,
+                        type_aggregation,
+                    )
+                },
+            );
+            let tie_policy_opt = parse_tie_policy(
+                &app_state_snapshot.tie_policy_str,
+                &app_state_snapshot.tie_attribute_str,
+            );
+
+            if let (Some(content_clone), false, Some((thresholds, tie_policy))) = (
+                app_state_snapshot.file_content.clone(),
+                app_state_snapshot.is_processing,
+                thresholds_opt.zip(tie_policy_opt),
+            ) {
+                dispatch(AppMessage::ProcessLog);
+
+                let file_name = app_state_snapshot.file_name.clone().unwrap_or_default();
+                let config = AnalysisConfig {
+                    thresholds,
+                    parse: app_state_snapshot.parse_options.clone(),
+                    csv: app_state_snapshot.csv_options.clone(),
+                    // The option is only offered for XES logs
+                    lifecycle_intervals: app_state_snapshot.lifecycle_intervals
+                        && app_state_snapshot.file_format == LogFormat::Xes,
+                    tie_policy,
+                    streaming: false,
+                    object_type: app_state_snapshot.ocel_object_type.clone(),
+                    matrix: MatrixOptions { include_self_loops: app_state_snapshot.include_self_loops },
+                    classification: ClassificationOptions { count_self_loops: app_state_snapshot.include_self_loops },
+                    rule_set_version: RULE_SET_VERSION,
+                };
+                let dispatch_clone = dispatch.clone();
+
+                spawn_local(async move {
+                    let result = analyze(&file_name, InputSource::Bytes(&content_clone), &config)
+                        .map(|analyses| {
+                            analyses
+                                .into_iter()
+                                .map(|analysis| {
+                                    let mut matrix_csv = Vec::new();
+                                    analysis.matrix.write_csv(&mut matrix_csv).expect("writing to memory cannot fail");
+                                    ClassifiedLog {
+                                        object_type: analysis.object_type,
+                                        output: analysis.output,
+                                        manifest: analysis.manifest,
+                                        matrix_csv: String::from_utf8(matrix_csv).expect("the matrix CSV is UTF-8"),
+                                    }
+                                })
+                                .collect()
+                        })
+                        .map_err(AppError::from);
+                    dispatch_clone(AppMessage::SetClassificationResult(result));
+                });
+            }
+        })
+    };
+
+    let current_app_state_for_view = (*app_state_handle).clone();
+
+    // Determine button disabled state for the view
+    let is_temporal_thresh_valid = parse_threshold_str(&current_app_state_for_view.temporal_threshold_str).is_some();
+    let is_existential_thresh_valid = parse_threshold_str(&current_app_state_for_view.existential_threshold_str).is_some();
+    let is_threshold_override_valid = |field: ThresholdField| {
+        current_app_state_for_view
+            .threshold_override_strs
+            .get(&field)
+            .is_none_or(|value| parse_threshold_override_str(value).is_some())
+    };
+    let are_threshold_overrides_valid = ThresholdField::ALL.into_iter().all(is_threshold_override_valid);
+    let is_direct_ratio = current_app_state_for_view.type_aggregation_str == "direct-ratio";
+    let is_type_aggregation_valid = parse_type_aggregation(
+        &current_app_state_for_view.type_aggregation_str,
+        &current_app_state_for_view.direct_ratio_str,
+    ).is_some();
+    let is_tie_attribute = current_app_state_for_view.tie_policy_str == "attribute";
+    let is_tie_policy_valid = parse_tie_policy(
+        &current_app_state_for_view.tie_policy_str,
+        &current_app_state_for_view.tie_attribute_str,
+    ).is_some();
+    let format = current_app_state_for_view.file_format;
+    let is_csv = format == LogFormat::Csv;
+    let is_csv_delimiter_valid = parse_delimiter_str(&current_app_state_for_view.csv_delimiter_str).is_some();
+    let is_classifier_selected = current_app_state_for_view.parse_options.classifier.is_some();
+    let is_activity_keys_valid = is_classifier_selected || !current_app_state_for_view.parse_options.activity_keys.is_empty();
+    let is_process_button_disabled = current_app_state_for_view.file_content.is_none() || 
+                                     current_app_state_for_view.is_processing ||
+                                     !is_temporal_thresh_valid ||
+                                     !is_existential_thresh_valid ||
+                                     !are_threshold_overrides_valid ||
+                                     !is_type_aggregation_valid ||
+                                     !is_tie_policy_valid ||
+                                     (is_csv && !is_csv_delimiter_valid) ||
+                                     (format == LogFormat::Xes && !is_activity_keys_valid);
+
+    html! {
+        <div class="container" style="padding: 20px; font-family: sans-serif;">
+            <h1>{ "Event Log Classifier" }</h1>
+
+            <div class="controls" style="margin-bottom: 20px; display: flex; gap: 20px; align-items: center;">
+                <div>
+                    <label for="xes-file" style="margin-right: 5px;">{ "Upload Event Log (XES, MXML, CSV or OCEL 2.0, optionally gzip-compressed):" }</label>
+                    <input type="file" id="xes-file" accept={accepted_file_types()} onchange={on_file_change} />
+                    if let Some(name) = current_app_state_for_view.file_name {
+                        <p style="font-size: 0.9em; margin-top: 5px;">{ format!("Selected: {}", name) }</p>
+                    }
+                </div>
+            </div>
+
+            if current_app_state_for_view.file_content.is_some() && format == LogFormat::Xes {
+                <div class="xes-options" style="margin-bottom: 20px; display: flex; gap: 20px; flex-wrap: wrap; align-items: center;">
+                    if !current_app_state_for_view.available_classifiers.is_empty() {
+                        <div>
+                            <label for="classifier" style="margin-right: 5px;">{ "Classifier:" }</label>
+                            <select id="classifier" onchange={on_classifier_change}>
+                                <option value="" selected={!is_classifier_selected}>{ "(Activity keys)" }</option>
+                                { for current_app_state_for_view.available_classifiers.iter().map(|classifier| html! {
+                                    <option
+                                        value={classifier.name.clone()}
+                                        selected={current_app_state_for_view.parse_options.classifier.as_ref() == Some(&classifier.name)}
+                                    >
+                                        { format!("{} ({})", classifier.name, classifier.keys.join(" ")) }
+                                    </option>
+                                }) }
+                            </select>
+                        </div>
+                    }
+                    <div>
+                        <label for="activity-keys" style="margin-right: 5px;">{ "Activity Key(s):" }</label>
+                        <input
+                            id="activity-keys"
+                            type="text"
+                            title="Space separated attribute keys, combined like an XES classifier"
+                            value={current_app_state_for_view.activity_keys_str.clone()}
+                            disabled={is_classifier_selected}
+                            oninput={on_xes_option_change(XesField::ActivityKeys)}
+                            style={if !is_activity_keys_valid {"border-color: red;"} else {""} }
+                        />
+                    </div>
+                    <div>
+                        <label for="timestamp-key" style="margin-right: 5px;">{ "Timestamp Key:" }</label>
+                        <input
+                            id="timestamp-key"
+                            type="text"
+                            value={current_app_state_for_view.parse_options.timestamp_key.clone()}
+                            oninput={on_xes_option_change(XesField::TimestampKey)}
+                        />
+                    </div>
+                    <div>
+                        <input
+                            id="keep-events-without-timestamp"
+                            type="checkbox"
+                            checked={current_app_state_for_view.parse_options.keep_events_without_timestamp}
+                            onchange={on_keep_events_without_timestamp_change}
+                        />
+                        <label for="keep-events-without-timestamp" style="margin-left: 5px;">{ "Keep events without timestamp" }</label>
+                    </div>
+                    <div>
+                        <input
+                            id="lifecycle-intervals"
+                            type="checkbox"
+                            checked={current_app_state_for_view.lifecycle_intervals}
+                            onchange={on_lifecycle_intervals_change}
+                        />
+                        <label for="lifecycle-intervals" style="margin-left: 5px;" title="Pair start/complete transitions; overlapping activities are not ordered">
+                            { "Lifecycle intervals (start/complete)" }
+                        </label>
+                    </div>
+                </div>
+            }
+
+            if is_csv {
+                <div class="csv-options" style="margin-bottom: 20px; display: flex; gap: 20px; flex-wrap: wrap;">
+                    <div>
+                        <label for="csv-case-column" style="margin-right: 5px;">{ "Case Column:" }</label>
+                        <input
+                            id="csv-case-column"
+                            type="text"
+                            value={current_app_state_for_view.csv_options.case_column.clone()}
+                            oninput={on_csv_option_change(CsvField::CaseColumn)}
+                        />
+                    </div>
+                    <div>
+                        <label for="csv-activity-column" style="margin-right: 5px;">{ "Activity Column:" }</label>
+                        <input
+                            id="csv-activity-column"
+                            type="text"
+                            value={current_app_state_for_view.csv_options.activity_column.clone()}
+                            oninput={on_csv_option_change(CsvField::ActivityColumn)}
+                        />
+                    </div>
+                    <div>
+                        <label for="csv-timestamp-column" style="margin-right: 5px;">{ "Timestamp Column:" }</label>
+                        <input
+                            id="csv-timestamp-column"
+                            type="text"
+                            value={current_app_state_for_view.csv_options.timestamp_column.clone()}
+                            oninput={on_csv_option_change(CsvField::TimestampColumn)}
+                        />
+                    </div>
+                    <div>
+                        <label for="csv-lifecycle-column" style="margin-right: 5px;">{ "Lifecycle Column (optional):" }</label>
+                        <input
+                            id="csv-lifecycle-column"
+                            type="text"
+                            placeholder="lifecycle:transition"
+                            value={current_app_state_for_view.csv_options.lifecycle_column.clone().unwrap_or_default()}
+                            oninput={on_csv_option_change(CsvField::LifecycleColumn)}
+                        />
+                    </div>
+                    <div>
+                        <label for="csv-delimiter" style="margin-right: 5px;">{ "Delimiter:" }</label>
+                        <input
+                            id="csv-delimiter"
+                            type="text"
+                            maxlength="1"
+                            value={current_app_state_for_view.csv_delimiter_str.clone()}
+                            oninput={on_csv_option_change(CsvField::Delimiter)}
+                            style={if !is_csv_delimiter_valid {"width: 30px; border-color: red;"} else {"width: 30px;"} }
+                        />
+                    </div>
+                    <div>
+                        <label for="csv-timestamp-format" style="margin-right: 5px;">{ "Timestamp Format (optional):" }</label>
+                        <input
+                            id="csv-timestamp-format"
+                            type="text"
+                            placeholder="%Y-%m-%d %H:%M:%S"
+                            value={current_app_state_for_view.csv_options.timestamp_format.clone().unwrap_or_default()}
+                            oninput={on_csv_option_change(CsvField::TimestampFormat)}
+                        />
+                    </div>
+                </div>
+            }
+
+            if format == LogFormat::Ocel && !current_app_state_for_view.ocel_object_types.is_empty() {
+                <div class="ocel-options" style="margin-bottom: 20px;">
+                    <label for="object-type" style="margin-right: 5px;">{ "Object Type:" }</label>
+                    <select id="object-type" onchange={on_object_type_change}>
+                        <option value="" selected={current_app_state_for_view.ocel_object_type.is_none()}>{ "(All object types)" }</option>
+                        { for current_app_state_for_view.ocel_object_types.iter().map(|object_type| html! {
+                            <option
+                                value={object_type.clone()}
+                                selected={current_app_state_for_view.ocel_object_type.as_ref() == Some(object_type)}
+                            >
+                                { object_type }
+                            </option>
+                        }) }
+                    </select>
+                </div>
+            }
+
+            <div class="thresholds" style="margin-bottom: 20px; display: flex; gap: 30px;">
+                <div>
+                    <label for="temporal-threshold" style="margin-right: 5px;">{ "Temporal Threshold (0.0-1.0):" }</label>
+                    <input
+                        id="temporal-threshold"
+                        type="number" 
+                        min="0.0" max="1.0" step="0.05"
+                        value={current_app_state_for_view.temporal_threshold_str.clone()} // Bind to string state
+                        oninput={on_temporal_threshold_change}
+                        style={if !is_temporal_thresh_valid && !current_app_state_for_view.temporal_threshold_str.is_empty() {"width: 70px; border-color: red;"} else {"width: 70px;"} }
+                    />
+                </div>
+                <div>
+                    <label for="existential-threshold" style="margin-right: 5px;">{ "Existential Threshold (0.0-1.0):" }</label>
+                    <input
+                        id="existential-threshold"
+                        type="number"
+                        min="0.0" max="1.0" step="0.05"
+                        value={current_app_state_for_view.existential_threshold_str.clone()} // Bind to string state
+                        oninput={on_existential_threshold_change}
+                        style={if !is_existential_thresh_valid && !current_app_state_for_view.existential_threshold_str.is_empty() {"width: 70px; border-color: red;"} else {"width: 70px;"} }
+                    />
+                </div>
+                <div>
+                    <input
+                        id="self-loops"
+                        type="checkbox"
+                        checked={current_app_state_for_view.include_self_loops}
+                        onchange={on_self_loops_change}
+                    />
+                    <label for="self-loops" style="margin-left: 5px;" title="Repeated occurrences of an activity within a trace">
+                        { "Self-loops" }
+                    </label>
+                </div>
+                <div>
+                    <label for="tie-policy" style="margin-right: 5px;" title="How events of a case with identical timestamps are ordered">
+                        { "Equal Timestamps:" }
+                    </label>
+                    <select id="tie-policy" onchange={on_tie_policy_change}>
+                        { for [
+                            ("document-order", "Keep document order"),
+                            ("concurrent", "Concurrent (record time gaps)"),
+                            ("attribute", "Order by attribute"),
+                        ].into_iter().map(|(value, label)| html! {
+                            <option value={value} selected={current_app_state_for_view.tie_policy_str == value}>{ label }</option>
+                        }) }
+                    </select>
+                    if is_tie_attribute {
+                        <input
+                            id="tie-attribute"
+                            type="text"
+                            value={current_app_state_for_view.tie_attribute_str.clone()}
+                            oninput={on_tie_attribute_change}
+                            style={if !is_tie_policy_valid {"margin-left: 5px; border-color: red;"} else {"margin-left: 5px;"} }
+                        />
+                    }
+                </div>
+            </div>
+
+            <details class="relation-thresholds" style="margin-bottom: 20px;">
+                <summary>{ "Thresholds per Relation (optional)" }</summary>
+                <div style="margin-top: 10px; display: flex; flex-wrap: wrap; gap: 20px;">
+                    { for ThresholdField::ALL.into_iter().map(|field| html! {
+                        <div>
+                            <label for={field.id()} style="margin-right: 5px;">{ field.label() }</label>
+                            <input
+                                id={field.id()}
+                                type="number"
+                                min="0.0" max="1.0" step="0.05"
+                                placeholder="default"
+                                value={current_app_state_for_view.threshold_override_strs.get(&field).cloned().unwrap_or_default()}
+                                oninput={on_threshold_override_change(field)}
+                                style={if !is_threshold_override_valid(field) {"width: 70px; border-color: red;"} else {"width: 70px;"} }
+                            />
+                        </div>
+                    }) }
+                </div>
+                <div style="margin-top: 10px;">
+                    <label for="type-aggregation" style="margin-right: 5px;" title="How the observations of a temporal relation decide between direct and eventual">
+                        { "Direct/Eventual:" }
+                    </label>
+                    <select id="type-aggregation" onchange={on_type_aggregation_change}>
+                        { for [
+                            ("any-eventual", "Eventual if any observation is eventual"),
+                            ("majority", "Majority of the observations"),
+                            ("direct-ratio", "Direct if at least a ratio of the observations is direct"),
+                        ].into_iter().map(|(value, label)| html! {
+                            <option value={value} selected={current_app_state_for_view.type_aggregation_str == value}>{ label }</option>
+                        }) }
+                    </select>
+                    if is_direct_ratio {
+                        <input
+                            id="direct-ratio"
+                            type="number"
+                            min="0.0" max="1.0" step="0.05"
+                            value={current_app_state_for_view.direct_ratio_str.clone()}
+                            oninput={on_direct_ratio_change}
+                            style={if !is_type_aggregation_valid {"width: 70px; margin-left: 5px; border-color: red;"} else {"width: 70px; margin-left: 5px;"} }
+                        />
+                    }
+                </div>
+            </details>
+
+            <button
+                onclick={on_process_log}
+                disabled={is_process_button_disabled}
+                style="padding: 10px 15px; font-size: 1em; cursor: pointer;"
+            >
+                { if current_app_state_for_view.is_processing { "Processing..." } else { "Process Log" } }
+            </button>
+
+            { // Display classification result
+                if let Some(result) = &current_app_state_for_view.classification_result {
+                    match result {
+                        Ok(classified_logs) => html! {
+                            { for classified_logs.iter().map(|ClassifiedLog { object_type, output, manifest, matrix_csv }| html! {
+                                <div class="result" style="margin-top: 20px; padding: 15px; border: 1px solid #ccc; border-radius: 5px;">
+                                    <h2 style="margin-top: 0;">
+                                        { match object_type {
+                                            Some(object_type) => format!("Classification Result: {}", object_type),
+                                            None => "Classification Result".to_string(),
+                                        } }
+                                    </h2>
+                                    <p><b>{ "Classification:" }</b> { &output.classification.to_string() }</p>
+                                    <p><b>{ "Temporal Type Aggregation:" }</b> { manifest.config.thresholds.temporal.type_aggregation.to_string() }</p>
+                                    <p><b>{ "Tie Policy:" }</b> { manifest.config.tie_policy.to_string() }</p>
+                                    if let Some(parse_report) = &manifest.parse_report {
+                                        <p><b>{ "Parse Report:" }</b> { parse_report.to_string() }</p>
+                                    }
+                                    <h3>{ "Matched Rules:" }</h3>
+                                    <ul>
+                                        { for output.matched_rules.iter().map(|rule| html!{ <li>{ rule }</li> }) }
+                                    </ul>
+                                    <details>
+                                        <summary title="Input, configuration and result, to reproduce this classification">{ "Manifest" }</summary>
+                                        <pre style="overflow-x: auto;">{ manifest.to_json() }</pre>
+                                    </details>
+                                    <p>
+                                        <a
+                                            href={format!("data:text/csv;charset=utf-8,{}", String::from(js_sys::encode_uri_component(matrix_csv)))}
+                                            download={match object_type {
+                                                Some(object_type) => format!("dependency-matrix.{}.csv", object_type),
+                                                None => "dependency-matrix.csv".to_string(),
+                                            }}
+                                        >
+                                            { "Download dependency matrix (CSV)" }
+                                        </a>
+                                    </p>
+                                </div>
+                            }) }
+                        },
+                        Err(e) => html! {
+                            <div class="error" style="color: red; margin-top: 20px;">
+                                { format!("Error: {}", e) }
+                            </div>
+                        }
+                    }
+                } else {
+                    html!{}
+                }
+            }
+        </div>
+    }
+}
+
+fn main() {
+    yew::Renderer::<App>::new().render();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OCEL_JSON: &str = r#"{
+  "objectTypes": [{"name": "order", "attributes": []}],
+  "eventTypes": [{"name": "place order", "attributes": []}],
+  "objects": [{"id": "o1", "type": "order"}],
+  "events": [
+    {"id": "e1", "type": "place order", "time": "2024-01-01T00:00:00Z",
+     "relationships": [{"objectId": "o1", "qualifier": ""}]}
+  ]
+}"#;
+
+    fn reduce(state: Rc<AppState>, msg: AppMessage) -> Rc<AppState> {
+        state.reduce(msg)
+    }
+
+    #[test]
+    fn test_accepted_file_types_cover_compressed_logs() {
+        let accepted = accepted_file_types();
+        for file_type in [".xes.gz", ".csv.gz", ".mxml.gz", ".xml.gz", ".jsonocel.gz", ".json"] {
+            assert!(accepted.split(',').any(|accepted| accepted == file_type), "{}", file_type);
+        }
+        // Every extension but XES is recognized by name, so none falls back to XES by accident
+        for extension in &LOG_EXTENSIONS[1..] {
+            assert_ne!(LogFormat::from_file_name(&format!("log.{}.gz", extension)), LogFormat::Xes);
+        }
+    }
+
+    #[test]
+    fn test_file_loaded_uses_the_selected_file_name() {
+        let state = Rc::new(AppState::default());
+        let state = reduce(state, AppMessage::FileSelected(Some("orders.jsonocel".to_string())));
+        let state = reduce(state, AppMessage::FileLoaded {
+            file_name: "orders.jsonocel".to_string(),
+            content: Ok(OCEL_JSON.as_bytes().to_vec()),
+        });
+        assert_eq!(state.file_name.as_deref(), Some("orders.jsonocel"));
+        assert_eq!(state.ocel_object_types, vec!["order"]);
+        assert!(state.file_content.is_some());
+
+        // The content of a file that was replaced by another selection is ignored
+        let state = reduce(state, AppMessage::FileSelected(Some("log.csv".to_string())));
+        let state = reduce(state, AppMessage::FileLoaded {
+            file_name: "orders.jsonocel".to_string(),
+            content: Ok(OCEL_JSON.as_bytes().to_vec()),
+        });
+        assert_eq!(state.file_name.as_deref(), Some("log.csv"));
+        assert!(state.file_content.is_none());
+        assert!(state.ocel_object_types.is_empty());
+    }
+
+    #[test]
+    fn test_file_loaded_detects_mxml_in_xml_files() {
+        let state = Rc::new(AppState::default());
+        let state = reduce(state, AppMessage::FileSelected(Some("legacy.xml".to_string())));
+        assert_eq!(state.file_format, LogFormat::Ocel);
+        let state = reduce(state, AppMessage::FileLoaded {
+            file_name: "legacy.xml".to_string(),
+            content: Ok(b"<WorkflowLog><Process/></WorkflowLog>".to_vec()),
+        });
+        assert_eq!(state.file_format, LogFormat::Mxml);
+        assert!(state.ocel_object_types.is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors_name_their_format() {
+        let mxml = AppError::from(Error::Parse(ParseError::InvalidMxmlTimestamp("noon".to_string())));
+        assert_eq!(mxml.to_string(), "Parsing error: Invalid MXML timestamp 'noon'");
+        let ocel = AppError::from(Error::Parse(ParseError::UnknownObjectType("item".to_string())));
+        assert!(matches!(ocel, AppError::OcelParse(_)));
+    }
+}
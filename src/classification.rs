@@ -1,6 +1,6 @@
 fn check_rule_ss3(p: &CalculatedPercentages) -> RuleCheckResult {
     let conds = vec![
-        p.none_none < 0.10,
+        p.none_weak() < 0.10,
         p.eventual_implication < 0.25,
         p.direct_any_existential < 0.15,
     ];
@@ -10,13 +10,74 @@ use crate::dependency_types::{
     dependency::Dependency, existential::DependencyType as ExistentialEnum,
     temporal::DependencyType as TemporalEnum,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
 pub type Activity = String;
 pub type InputMatrix = HashMap<(Activity, Activity), Dependency>;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+/// A dependency matrix the classification can be computed on, e.g. an [`InputMatrix`]
+/// or a [`crate::DependencyMatrix`].
+pub trait ClassifiableMatrix {
+    /// The temporal and existential dependency type of every cell between two
+    /// distinct activities.
+    fn dependency_types(
+        &self,
+    ) -> impl Iterator<Item = (Option<TemporalEnum>, Option<ExistentialEnum>)>;
+
+    /// The dependency types of the self-loop cells (the diagonal), if the matrix has them.
+    fn self_loop_dependency_types(
+        &self,
+    ) -> impl Iterator<Item = (Option<TemporalEnum>, Option<ExistentialEnum>)> {
+        std::iter::empty()
+    }
+}
+
+/// Version of the classification rules and percentages below, recorded in every
+/// [`crate::Manifest`]. Bump it whenever a change can alter the classification of a log.
+pub const RULE_SET_VERSION: u32 = 2;
+
+/// Options of the classification, see [`classify_matrix_with_options`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClassificationOptions {
+    /// Count the self-loop cells of the matrix in the percentages, like any other cell.
+    /// Without this, the rules only see the cells between distinct activities.
+    pub count_self_loops: bool,
+}
+
+fn cell_dependency_types(dependency_obj: &Dependency) -> (Option<TemporalEnum>, Option<ExistentialEnum>) {
+    (
+        dependency_obj
+            .temporal_dependency
+            .as_ref()
+            .map(|td| td.dependency_type),
+        dependency_obj
+            .existential_dependency
+            .as_ref()
+            .map(|ed| ed.dependency_type),
+    )
+}
+
+impl ClassifiableMatrix for InputMatrix {
+    fn dependency_types(
+        &self,
+    ) -> impl Iterator<Item = (Option<TemporalEnum>, Option<ExistentialEnum>)> {
+        self.iter()
+            .filter(|((from, to), _)| from != to)
+            .map(|(_, dependency_obj)| cell_dependency_types(dependency_obj))
+    }
+
+    fn self_loop_dependency_types(
+        &self,
+    ) -> impl Iterator<Item = (Option<TemporalEnum>, Option<ExistentialEnum>)> {
+        self.iter()
+            .filter(|((from, to), _)| from == to)
+            .map(|(_, dependency_obj)| cell_dependency_types(dependency_obj))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Classification {
     Structured,
     SemiStructured,
@@ -43,7 +104,7 @@ impl std::fmt::Display for Classification {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ClassificationOutput {
     pub classification: Classification,
     pub matched_rules: Vec<String>,
@@ -60,43 +121,71 @@ pub struct CalculatedPercentages {
 
     // Secondary Rule specific percentages
     none_negated_equivalence: f64,
+    none_nand: f64,
+    none_or: f64,
 
     // Unstructured Rule related percentages
     eventual_any_existential: f64, // Any existential with Eventual temporal
     direct_any_existential: f64, // Any existential with Direct temporal (for completeness if needed in the future)
     direct_none: f64,
+
+    // Parallel temporal dependencies. These cells have no order and are also counted
+    // in the none_* percentages above.
+    parallel_any_existential: f64, // Concurrency: occur together, in either order
+    parallel_none: f64, // Both orders, without an existential dependency
+    ordered: f64, // Any Direct or Eventual temporal
 }
 
 impl CalculatedPercentages {
-    pub fn new(matrix: &InputMatrix) -> Result<Self, String> {
-        if matrix.is_empty() {
-            return Err("Input matrix is empty".to_string());
-        }
+    pub fn new<M: ClassifiableMatrix + ?Sized>(matrix: &M) -> Result<Self, String> {
+        Self::new_with_options(matrix, &ClassificationOptions::default())
+    }
 
-        let total_entries = matrix.len();
+    pub fn new_with_options<M: ClassifiableMatrix + ?Sized>(
+        matrix: &M,
+        options: &ClassificationOptions,
+    ) -> Result<Self, String> {
+        let self_loops = options
+            .count_self_loops
+            .then(|| matrix.self_loop_dependency_types())
+            .into_iter()
+            .flatten();
+
+        let mut total_entries = 0;
         let mut counts_none_none = 0;
         let mut counts_none_implication = 0;
         let mut counts_none_equivalence = 0;
         let mut counts_none_negated_equivalence = 0;
+        let mut counts_none_nand = 0;
+        let mut counts_none_or = 0;
         let mut counts_eventual_equivalence = 0;
         let mut counts_eventual_implication = 0;
         let mut counts_eventual_any = 0;
         let mut counts_direct_any = 0; // For direct_any_existential
         let mut counts_direct_none = 0;
-
-        for dependency_obj in matrix.values() {
-            let temporal_type = dependency_obj
-                .temporal_dependency
-                .as_ref()
-                .map(|td| td.dependency_type);
-            let existential_type = dependency_obj
-                .existential_dependency
-                .as_ref()
-                .map(|ed| ed.dependency_type);
-
+        let mut counts_parallel_any = 0;
+        let mut counts_parallel_none = 0;
+        let mut counts_ordered = 0;
+
+        for (temporal_type, existential_type) in matrix.dependency_types().chain(self_loops) {
+            total_entries += 1;
+            // Next to a temporal dependency, NAND and OR count as no existential dependency
+            // (see `none_weak`)
+            let strong_existential = existential_type
+                .filter(|t| !matches!(t, ExistentialEnum::Nand | ExistentialEnum::Or));
+            if matches!(temporal_type, Some(TemporalEnum::Direct | TemporalEnum::Eventual)) {
+                counts_ordered += 1;
+            }
+            if temporal_type == Some(TemporalEnum::Parallel) {
+                if strong_existential.is_some() {
+                    counts_parallel_any += 1;
+                } else {
+                    counts_parallel_none += 1;
+                }
+            }
             match temporal_type {
-                None => {
-                    // No temporal dependency
+                None | Some(TemporalEnum::Parallel) => {
+                    // No temporal order
                     match existential_type {
                         None => counts_none_none += 1,
                         Some(ExistentialEnum::Implication) => counts_none_implication += 1,
@@ -104,13 +193,15 @@ impl CalculatedPercentages {
                         Some(ExistentialEnum::NegatedEquivalence) => {
                             counts_none_negated_equivalence += 1
                         }
-                        Some(ExistentialEnum::Nand) | Some(ExistentialEnum::Or) => {
+                        Some(ExistentialEnum::Nand) => counts_none_nand += 1,
+                        Some(ExistentialEnum::Or) => counts_none_or += 1,
+                        Some(ExistentialEnum::Repetition) => {
                             // Not consider at the moment
                         }
                     }
                 }
                 Some(TemporalEnum::Eventual) => {
-                    if existential_type.is_some() {
+                    if strong_existential.is_some() {
                         // Any existential with Eventual temporal
                         counts_eventual_any += 1;
                     }
@@ -121,7 +212,7 @@ impl CalculatedPercentages {
                     }
                 }
                 Some(TemporalEnum::Direct) => {
-                    if existential_type.is_some() {
+                    if strong_existential.is_some() {
                         // Any existential with Direct temporal
                         counts_direct_any += 1;
                     } else {
@@ -132,6 +223,10 @@ impl CalculatedPercentages {
             }
         }
 
+        if total_entries == 0 {
+            return Err("Input matrix is empty".to_string());
+        }
+
         let total_f = total_entries as f64;
         Ok(Self {
             none_none: counts_none_none as f64 / total_f,
@@ -140,13 +235,35 @@ impl CalculatedPercentages {
             eventual_equivalence: counts_eventual_equivalence as f64 / total_f,
             eventual_implication: counts_eventual_implication as f64 / total_f,
             none_negated_equivalence: counts_none_negated_equivalence as f64 / total_f,
+            none_nand: counts_none_nand as f64 / total_f,
+            none_or: counts_none_or as f64 / total_f,
             eventual_any_existential: counts_eventual_any as f64 / total_f,
             direct_any_existential: counts_direct_any as f64 / total_f,
             direct_none: counts_direct_none as f64 / total_f,
+            parallel_any_existential: counts_parallel_any as f64 / total_f,
+            parallel_none: counts_parallel_none as f64 / total_f,
+            ordered: counts_ordered as f64 / total_f,
         })
     }
 }
 
+impl CalculatedPercentages {
+    /// Cells without temporal and without a strong existential dependency. NAND and OR
+    /// are as weak as no dependency for the rules: they hold vacuously for rarely
+    /// respectively frequently occurring activities.
+    fn none_weak(&self) -> f64 {
+        self.none_none + self.none_nand + self.none_or
+    }
+
+    /// The missing order comes from concurrency rather than chaos: activities that occur
+    /// together in either order sit between ordered activities (like the branches of a
+    /// parallel block), so the unstructured rules do not apply. A log without any order
+    /// stays unstructured.
+    fn is_concurrent(&self) -> bool {
+        self.parallel_any_existential >= 0.10 && self.ordered >= 0.10
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum RuleCategory {
     Structured,
@@ -167,7 +284,8 @@ type RuleCheckResult = (bool, Vec<bool>);
 fn check_rule_u1(p: &CalculatedPercentages) -> bool {
     // println!("Checking U1 rule: none_none > 0.80 ({}) && eventual_any_existential < 0.10 ({}) && direct_any_existential < 0.10 ({})",
     //     p.none_none, p.eventual_any_existential, p.direct_any_existential);
-    (p.none_none > 0.80) && (p.eventual_any_existential < 0.10) && (p.direct_any_existential < 0.10)
+    (p.none_weak() > 0.80) && (p.eventual_any_existential < 0.10) && (p.direct_any_existential < 0.10)
+        && !p.is_concurrent()
 }
 
 fn check_rule_u2(p: &CalculatedPercentages) -> bool {
@@ -175,12 +293,12 @@ fn check_rule_u2(p: &CalculatedPercentages) -> bool {
     //     "Checking U2 rule: none_equivalence > 0.80 ({})",
     //     p.none_equivalence
     // );
-    p.none_equivalence > 0.80
+    p.none_equivalence > 0.80 && !p.is_concurrent()
 }
 
 fn check_rule_s1(p: &CalculatedPercentages) -> RuleCheckResult {
     let conds = vec![
-        p.none_none < 0.05,
+        p.none_weak() < 0.05,
         p.none_implication < 0.10,
         p.eventual_equivalence > 0.10,
         p.eventual_implication > 0.40,
@@ -192,7 +310,7 @@ fn check_rule_s1(p: &CalculatedPercentages) -> RuleCheckResult {
 
 fn check_rule_s2(p: &CalculatedPercentages) -> RuleCheckResult {
     let conds = vec![
-        p.none_none < 0.05,
+        p.none_weak() < 0.05,
         p.none_implication <= 0.15,
         p.eventual_equivalence >= 0.10,
         p.eventual_implication > 0.30,
@@ -210,7 +328,7 @@ fn check_rule_s3(p: &CalculatedPercentages) -> RuleCheckResult {
 
 fn check_rule_ss1(p: &CalculatedPercentages) -> RuleCheckResult {
     let conds = vec![
-        p.none_none < 0.35,
+        p.none_weak() < 0.35,
         p.none_implication > 0.30,
         p.eventual_equivalence < 0.05,
         p.eventual_implication < 0.20,
@@ -222,7 +340,7 @@ fn check_rule_ss1(p: &CalculatedPercentages) -> RuleCheckResult {
 
 fn check_rule_ss2(p: &CalculatedPercentages) -> RuleCheckResult {
     let conds = vec![
-        p.none_none < 0.25,
+        p.none_weak() < 0.25,
         p.none_implication > 0.01,
         p.eventual_equivalence > 0.10,
         p.eventual_implication < 0.40,
@@ -234,7 +352,7 @@ fn check_rule_ss2(p: &CalculatedPercentages) -> RuleCheckResult {
 
 fn check_rule_ls1(p: &CalculatedPercentages) -> RuleCheckResult {
     let conds = vec![
-        p.none_none > 0.20,
+        p.none_weak() > 0.20,
         p.none_implication < 0.35,
         p.eventual_equivalence < 0.10,
         p.eventual_implication < 0.30,
@@ -246,7 +364,7 @@ fn check_rule_ls1(p: &CalculatedPercentages) -> RuleCheckResult {
 
 fn check_rule_ls2(p: &CalculatedPercentages) -> RuleCheckResult {
     let conds = vec![
-        p.none_none > 0.50,
+        p.none_weak() > 0.50,
         p.none_implication < 0.10,
         p.eventual_equivalence < 0.05,
         p.eventual_implication < 0.25,
@@ -326,7 +444,7 @@ fn apply_primary_rules(
 
 fn check_rule_bs1(p: &CalculatedPercentages) -> RuleCheckResult {
     let conds = vec![
-        p.none_none < 0.10,
+        p.none_weak() < 0.10,
         p.none_negated_equivalence > 0.50, // This implies event_implication and eventual_equivalence are low.
         p.eventual_implication > 0.60, // This might conflict with none_negated_equivalence > 0.50 if they share matrix entries
     ];
@@ -336,7 +454,7 @@ fn check_rule_bs1(p: &CalculatedPercentages) -> RuleCheckResult {
 }
 
 fn check_rule_bs2(p: &CalculatedPercentages) -> RuleCheckResult {
-    let conds = vec![p.none_none < 0.20, p.none_implication > 0.40];
+    let conds = vec![p.none_weak() < 0.20, p.none_implication > 0.40];
     // println!(
     //     "Checking BS2 rule: none_none < 0.20 ({}), none_implication > 0.40 ({})",
     //     p.none_none, p.none_implication
@@ -345,7 +463,7 @@ fn check_rule_bs2(p: &CalculatedPercentages) -> RuleCheckResult {
 }
 
 fn check_rule_bl1(p: &CalculatedPercentages) -> RuleCheckResult {
-    let conds = vec![p.none_none > 0.60, p.none_implication < 0.30];
+    let conds = vec![p.none_weak() > 0.60, p.none_implication < 0.30];
     // println!(
     //     "Checking BL1 rule: none_none > 0.60 ({}), none_implication < 0.30 ({})",
     //     p.none_none, p.none_implication
@@ -476,8 +594,15 @@ fn calculate_by_most_indicators(
     }
 }
 
-pub fn classify_matrix(matrix: &InputMatrix) -> ClassificationOutput {
-    let percentages = match CalculatedPercentages::new(matrix) {
+pub fn classify_matrix<M: ClassifiableMatrix + ?Sized>(matrix: &M) -> ClassificationOutput {
+    classify_matrix_with_options(matrix, &ClassificationOptions::default())
+}
+
+pub fn classify_matrix_with_options<M: ClassifiableMatrix + ?Sized>(
+    matrix: &M,
+    options: &ClassificationOptions,
+) -> ClassificationOutput {
+    let percentages = match CalculatedPercentages::new_with_options(matrix, options) {
         Ok(p) => p,
         Err(e) => {
             return ClassificationOutput {
@@ -782,6 +907,67 @@ mod tests {
     assert_eq!(classify_matrix(&matrix).classification, Classification::SemiStructured);
     }
 
+    #[test]
+    fn test_nand_and_or_are_counted() {
+        let mut matrix = build_detailed_matrix_from_counts_array([2, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        matrix.insert(("X".to_string(), "Y".to_string()), dd("X", "Y", None, Some((ExistentialEnum::Nand, e_both()))));
+        matrix.insert(("Y".to_string(), "X".to_string()), dd("Y", "X", None, Some((ExistentialEnum::Or, e_both()))));
+
+        let percentages = CalculatedPercentages::new(&matrix).unwrap();
+        assert_eq!(percentages.none_none, 0.5);
+        assert_eq!(percentages.none_nand, 0.25);
+        assert_eq!(percentages.none_or, 0.25);
+        // Like no dependency for the rules, e.g. U1 for a log where all pairs only satisfy OR
+        assert_eq!(classify_matrix(&matrix).classification, Classification::Unstructured);
+    }
+
+    #[test]
+    fn test_parallel_blocks_are_not_unstructured() {
+        let parallel = |matrix: &mut InputMatrix, count: usize| {
+            for i in 0..count {
+                let (from, to) = (format!("P{}", i), format!("Q{}", i));
+                let dependency = dd(&from, &to, Some((TemporalEnum::Parallel, TemporalDirection::Both)), Some((e_eq(), e_both())));
+                matrix.insert((from, to), dependency);
+            }
+        };
+
+        // Activities that always occur together, but in no order: U2
+        let matrix = build_detailed_matrix_from_counts_array([0, 0, 18, 0, 2, 0, 0, 0, 0, 0]);
+        assert_eq!(classify_matrix(&matrix).matched_rules, vec!["U2"]);
+
+        // Some of them in either order next to ordered activities: concurrency
+        let mut matrix = build_detailed_matrix_from_counts_array([0, 0, 16, 0, 2, 0, 0, 0, 0, 0]);
+        parallel(&mut matrix, 2);
+        let percentages = CalculatedPercentages::new(&matrix).unwrap();
+        assert_eq!(percentages.none_equivalence, 0.9);
+        assert_eq!(percentages.parallel_any_existential, 0.1);
+        assert_eq!(percentages.ordered, 0.1);
+        assert_ne!(classify_matrix(&matrix).classification, Classification::Unstructured);
+
+        // All of them in either order, without anything ordered: still unstructured
+        let mut matrix = InputMatrix::new();
+        parallel(&mut matrix, 6);
+        assert_eq!(classify_matrix(&matrix).classification, Classification::Unstructured);
+    }
+
+    #[test]
+    fn test_self_loops_counted_only_with_option() {
+        let mut matrix = build_detailed_matrix_from_counts_array([1, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        matrix.insert(
+            ("A".to_string(), "A".to_string()),
+            dd("A", "A", Some((t_ev(), t_fwd())), Some((ExistentialEnum::Repetition, e_both()))),
+        );
+
+        let percentages = CalculatedPercentages::new(&matrix).unwrap();
+        assert_eq!(percentages.none_none, 1.0);
+        assert_eq!(percentages.eventual_any_existential, 0.0);
+
+        let options = ClassificationOptions { count_self_loops: true };
+        let percentages = CalculatedPercentages::new_with_options(&matrix, &options).unwrap();
+        assert_eq!(percentages.none_none, 0.5);
+        assert_eq!(percentages.eventual_any_existential, 0.5);
+    }
+
     // Synthetic logs tests
     #[test]
     fn test_log01_structured() {
@@ -0,0 +1,356 @@
+use crate::classification::{ClassifiableMatrix, InputMatrix};
+use crate::dependency_types::{
+    dependency::{Dependency, DependencyStatistics},
+    existential::{self, ExistentialDependency},
+    temporal::{self, duration_seconds, TemporalDependency},
+};
+use crate::event_log::{ActivityId, ActivityTable};
+
+/// The dependency between two activities of a [`DependencyMatrix`], without the
+/// activity names. Its statistics are kept apart, see [`DependencyMatrix::statistics`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DependencyCell {
+    pub temporal: Option<(temporal::DependencyType, temporal::Direction)>,
+    pub existential: Option<(existential::DependencyType, existential::Direction)>,
+}
+
+impl DependencyCell {
+    pub fn new(
+        temporal_dependency: Option<&TemporalDependency>,
+        existential_dependency: Option<&ExistentialDependency>,
+    ) -> Self {
+        Self {
+            temporal: temporal_dependency.map(|dep| (dep.dependency_type, dep.direction)),
+            existential: existential_dependency.map(|dep| (dep.dependency_type, dep.direction)),
+        }
+    }
+
+    /// Expands the cell into a [`Dependency`] between `from` and `to`, without statistics.
+    pub fn to_dependency(&self, from: &str, to: &str) -> Dependency {
+        Dependency::new(
+            from.to_string(),
+            to.to_string(),
+            self.temporal.map(|(dependency_type, direction)| {
+                TemporalDependency::new(from, to, dependency_type, direction)
+            }),
+            self.existential.map(|(dependency_type, direction)| {
+                ExistentialDependency::new(from, to, dependency_type, direction)
+            }),
+        )
+    }
+}
+
+/// A dependency matrix stored densely: one cell per ordered pair of activities,
+/// indexed by the [`ActivityId`]s of its activity table.
+///
+/// Activity names are stored once in the table instead of in every dependency, and
+/// iteration follows the activity ids, so it is deterministic. Cells that were not
+/// computed (e.g. self-loops, unless requested) are empty. [`DependencyMatrix::to_input_matrix`]
+/// converts it to the map based [`InputMatrix`].
+///
+/// The [`DependencyStatistics`] of the cells are stored in a separate vector, which is
+/// only allocated once statistics are inserted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependencyMatrix {
+    activities: ActivityTable,
+    cells: Vec<Option<DependencyCell>>,
+    statistics: Vec<Option<DependencyStatistics>>,
+}
+
+impl DependencyMatrix {
+    /// Creates a matrix with empty cells for the given activities.
+    pub fn new(activities: ActivityTable) -> Self {
+        let cells = vec![None; activities.len() * activities.len()];
+        Self {
+            activities,
+            cells,
+            statistics: Vec::new(),
+        }
+    }
+
+    pub fn activities(&self) -> &ActivityTable {
+        &self.activities
+    }
+
+    fn index(&self, from: ActivityId, to: ActivityId) -> usize {
+        from as usize * self.activities.len() + to as usize
+    }
+
+    pub fn insert(&mut self, from: ActivityId, to: ActivityId, cell: DependencyCell) {
+        let index = self.index(from, to);
+        self.cells[index] = Some(cell);
+    }
+
+    pub fn get(&self, from: ActivityId, to: ActivityId) -> Option<&DependencyCell> {
+        self.cells[self.index(from, to)].as_ref()
+    }
+
+    pub fn get_by_name(&self, from: &str, to: &str) -> Option<&DependencyCell> {
+        self.get(self.activities.id(from)?, self.activities.id(to)?)
+    }
+
+    /// Records the statistics the cell between `from` and `to` was decided on.
+    pub fn insert_statistics(
+        &mut self,
+        from: ActivityId,
+        to: ActivityId,
+        statistics: DependencyStatistics,
+    ) {
+        let index = self.index(from, to);
+        if self.statistics.is_empty() {
+            self.statistics = vec![None; self.cells.len()];
+        }
+        self.statistics[index] = Some(statistics);
+    }
+
+    /// The statistics of the cell between `from` and `to`, if recorded.
+    pub fn statistics(&self, from: ActivityId, to: ActivityId) -> Option<&DependencyStatistics> {
+        self.statistics.get(self.index(from, to))?.as_ref()
+    }
+
+    pub fn statistics_by_name(&self, from: &str, to: &str) -> Option<&DependencyStatistics> {
+        self.statistics(self.activities.id(from)?, self.activities.id(to)?)
+    }
+
+    /// Expands the cell between `from` and `to` into a [`Dependency`] with its statistics.
+    pub fn dependency(&self, from: ActivityId, to: ActivityId) -> Option<Dependency> {
+        let dependency = self
+            .get(from, to)?
+            .to_dependency(self.activities.name(from), self.activities.name(to));
+        Some(match self.statistics(from, to) {
+            Some(statistics) => dependency.with_statistics(*statistics),
+            None => dependency,
+        })
+    }
+
+    /// Number of non-empty cells.
+    pub fn len(&self) -> usize {
+        self.cells.iter().flatten().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The non-empty cells, row by row in order of the activity ids.
+    pub fn iter(&self) -> impl Iterator<Item = (ActivityId, ActivityId, &DependencyCell)> {
+        let size = self.activities.len().max(1);
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, cell)| {
+                let cell = cell.as_ref()?;
+                Some((
+                    (index / size) as ActivityId,
+                    (index % size) as ActivityId,
+                    cell,
+                ))
+            })
+    }
+
+    /// Converts the matrix to the map based [`InputMatrix`].
+    pub fn to_input_matrix(&self) -> InputMatrix {
+        self.iter()
+            .filter_map(|(from, to, _)| {
+                let dependency = self.dependency(from, to)?;
+                let key = (dependency.from.clone(), dependency.to.clone());
+                Some((key, dependency))
+            })
+            .collect()
+    }
+
+    /// Writes the non-empty cells as CSV, one row per dependency with its statistics and
+    /// the min/median/max time gap in seconds. Unknown values are left empty.
+    pub fn write_csv<W: std::io::Write>(&self, writer: W) -> csv::Result<()> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record([
+            "from",
+            "to",
+            "temporal",
+            "existential",
+            "relevant_traces",
+            "forward_ratio",
+            "backward_ratio",
+            "implication_ratio",
+            "reverse_implication_ratio",
+            "negated_equivalence_ratio",
+            "nand_ratio",
+            "or_ratio",
+            "min_time_gap_seconds",
+            "median_time_gap_seconds",
+            "max_time_gap_seconds",
+        ])?;
+
+        let text = |value: Option<String>| value.unwrap_or_default();
+        for (from_id, to_id, cell) in self.iter() {
+            let (from, to) = (self.activities.name(from_id), self.activities.name(to_id));
+            let dependency = cell.to_dependency(from, to);
+            let statistics = self.statistics(from_id, to_id);
+            let ratio = |ratio: fn(&DependencyStatistics) -> Option<f64>| {
+                text(statistics.and_then(ratio).map(|r| r.to_string()))
+            };
+            let gap = |gap: fn(&temporal::TimeGaps) -> chrono::Duration| {
+                text(
+                    statistics
+                        .and_then(|statistics| statistics.time_gaps.as_ref())
+                        .map(|time_gaps| duration_seconds(gap(time_gaps)).to_string()),
+                )
+            };
+            writer.write_record([
+                from.to_string(),
+                to.to_string(),
+                text(dependency.temporal_dependency.map(|dep| dep.to_string())),
+                text(dependency.existential_dependency.map(|dep| dep.to_string())),
+                text(statistics.map(|statistics| statistics.relevant_traces().to_string())),
+                ratio(|statistics| statistics.temporal.forward_ratio()),
+                ratio(|statistics| statistics.temporal.backward_ratio()),
+                ratio(|statistics| statistics.existential.implication_ratio()),
+                ratio(|statistics| statistics.existential.reverse_implication_ratio()),
+                ratio(|statistics| statistics.existential.negated_equivalence_ratio()),
+                ratio(|statistics| statistics.existential.nand_ratio()),
+                ratio(|statistics| statistics.existential.or_ratio()),
+                gap(|time_gaps| time_gaps.min),
+                gap(|time_gaps| time_gaps.median),
+                gap(|time_gaps| time_gaps.max),
+            ])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl DependencyMatrix {
+    fn cell_dependency_types(
+        &self,
+        self_loops: bool,
+    ) -> impl Iterator<
+        Item = (
+            Option<temporal::DependencyType>,
+            Option<existential::DependencyType>,
+        ),
+    > + '_ {
+        self.iter()
+            .filter(move |(from, to, _)| (from == to) == self_loops)
+            .map(|(_, _, cell)| {
+                (
+                    cell.temporal.map(|(dependency_type, _)| dependency_type),
+                    cell.existential.map(|(dependency_type, _)| dependency_type),
+                )
+            })
+    }
+}
+
+impl ClassifiableMatrix for DependencyMatrix {
+    fn dependency_types(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            Option<temporal::DependencyType>,
+            Option<existential::DependencyType>,
+        ),
+    > {
+        self.cell_dependency_types(false)
+    }
+
+    fn self_loop_dependency_types(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            Option<temporal::DependencyType>,
+            Option<existential::DependencyType>,
+        ),
+    > {
+        self.cell_dependency_types(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classification::{classify_matrix, CalculatedPercentages};
+    use crate::matrix_generation::{
+        generate_dependency_matrix, generate_indexed_dependency_matrix, MatrixOptions,
+    };
+
+    #[test]
+    fn test_dependency_matrix_matches_input_matrix() {
+        let traces = vec![
+            vec!["C", "A", "B"],
+            vec!["A", "B", "C"],
+            vec!["A", "D"],
+            vec!["A", "B", "C"],
+        ];
+        let matrix =
+            generate_indexed_dependency_matrix(&traces, 0.7, 0.7, &MatrixOptions::default());
+
+        // Activities are indexed in sorted order, self-loops are left out
+        assert_eq!(matrix.activities().names(), &["A", "B", "C", "D"]);
+        assert_eq!(matrix.len(), 12);
+        let pairs: Vec<(ActivityId, ActivityId)> = matrix
+            .iter()
+            .map(|(from, to, _)| (from, to))
+            .take(4)
+            .collect();
+        assert_eq!(pairs, vec![(0, 1), (0, 2), (0, 3), (1, 0)]);
+        assert_eq!(matrix.get_by_name("A", "A"), None);
+        assert_eq!(
+            matrix.get_by_name("A", "B").unwrap().temporal,
+            Some((
+                temporal::DependencyType::Direct,
+                temporal::Direction::Forward
+            ))
+        );
+
+        assert_eq!(
+            matrix
+                .statistics_by_name("A", "B")
+                .map(|statistics| statistics.existential.both),
+            Some(3)
+        );
+        assert_eq!(matrix.statistics_by_name("A", "A"), None);
+        // Statistics are kept out of the cells, which stay small
+        assert!(std::mem::size_of::<Option<DependencyCell>>() <= 8);
+
+        let input_matrix = matrix.to_input_matrix();
+        assert_eq!(input_matrix, generate_dependency_matrix(&traces, 0.7, 0.7));
+        assert_eq!(classify_matrix(&matrix), classify_matrix(&input_matrix));
+        assert_eq!(
+            CalculatedPercentages::new(&matrix),
+            CalculatedPercentages::new(&input_matrix)
+        );
+    }
+
+    #[test]
+    fn test_write_csv() {
+        use crate::event_log::ActivityInstance;
+        use crate::matrix_generation::generate_indexed_interval_dependency_matrix;
+        use chrono::TimeZone;
+
+        let at = |activity: &str, minute| {
+            let time = chrono::Utc
+                .with_ymd_and_hms(2024, 1, 1, 0, minute, 0)
+                .unwrap();
+            ActivityInstance::new(activity.to_string(), time, time)
+        };
+        let traces = vec![vec![at("A", 0), at("B", 1)], vec![at("A", 0), at("B", 3)]];
+        let matrix = generate_indexed_interval_dependency_matrix(
+            &traces,
+            1.0,
+            1.0,
+            &MatrixOptions::default(),
+        );
+
+        let mut csv = Vec::new();
+        matrix.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "from,to,temporal,existential,relevant_traces,forward_ratio,backward_ratio,\
+             implication_ratio,reverse_implication_ratio,negated_equivalence_ratio,nand_ratio,\
+             or_ratio,min_time_gap_seconds,median_time_gap_seconds,max_time_gap_seconds"
+        );
+        assert_eq!(lines[1], "A,B,A ≺d B,A <=> B,2,1,0,1,1,0,0,1,60,120,180");
+    }
+}
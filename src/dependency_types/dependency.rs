@@ -1,5 +1,5 @@
-use crate::dependency_types::existential::ExistentialDependency;
-use crate::dependency_types::temporal::TemporalDependency;
+use crate::dependency_types::existential::{CooccurrenceCounts, ExistentialDependency};
+use crate::dependency_types::temporal::{RelationCounts, TemporalDependency, TimeGaps};
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Dependency {
@@ -7,6 +7,53 @@ pub struct Dependency {
     pub to: String,
     pub temporal_dependency: Option<TemporalDependency>,
     pub existential_dependency: Option<ExistentialDependency>,
+    /// The observations both dependencies were decided on, if known.
+    pub statistics: Option<DependencyStatistics>,
+}
+
+/// Support of the dependencies between two activities: the observed temporal relations,
+/// their time gaps if the log was ordered by timestamps, and the (weighted) trace counts
+/// of the existential relations.
+///
+/// The ratios of all candidate relations can be read from it, to see how close a verdict
+/// was to the threshold or to ignore dependencies backed by only a few traces.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DependencyStatistics {
+    pub temporal: RelationCounts,
+    pub existential: CooccurrenceCounts,
+    pub time_gaps: Option<TimeGaps>,
+}
+
+impl DependencyStatistics {
+    /// Number of traces containing at least one of the two activities.
+    pub fn relevant_traces(&self) -> usize {
+        self.existential.relevant()
+    }
+}
+
+impl std::fmt::Display for DependencyStatistics {
+    /// Writes the number of relevant traces followed by the ratio of every candidate
+    /// relation ("-" if there was nothing to observe) and the time gaps, if known.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ratio = |ratio: Option<f64>| ratio.map_or("-".to_string(), |r| format!("{:.2}", r));
+        let existential = &self.existential;
+        write!(
+            f,
+            "relevant traces: {}, forward: {}, backward: {}, implication: {}, reverse implication: {}, negated equivalence: {}, nand: {}, or: {}",
+            self.relevant_traces(),
+            ratio(self.temporal.forward_ratio()),
+            ratio(self.temporal.backward_ratio()),
+            ratio(existential.implication_ratio()),
+            ratio(existential.reverse_implication_ratio()),
+            ratio(existential.negated_equivalence_ratio()),
+            ratio(existential.nand_ratio()),
+            ratio(existential.or_ratio()),
+        )?;
+        match &self.time_gaps {
+            Some(time_gaps) => write!(f, ", time gap: {}", time_gaps),
+            None => Ok(()),
+        }
+    }
 }
 
 impl Dependency {
@@ -21,8 +68,14 @@ impl Dependency {
             to,
             temporal_dependency,
             existential_dependency,
+            statistics: None,
         }
     }
+
+    pub fn with_statistics(mut self, statistics: DependencyStatistics) -> Self {
+        self.statistics = Some(statistics);
+        self
+    }
 }
 
 impl std::fmt::Display for Dependency {
@@ -1,3 +1,6 @@
+use super::WeightedTrace;
+use serde::{Deserialize, Serialize};
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ExistentialDependency {
     pub from: String,
@@ -34,6 +37,7 @@ impl std::fmt::Display for ExistentialDependency {
             DependencyType::NegatedEquivalence => write!(f, "{} <~> {}", self.from, self.to),
             DependencyType::Nand => write!(f, "{} NAND {}", self.from, self.to),
             DependencyType::Or => write!(f, "{} OR {}", self.from, self.to),
+            DependencyType::Repetition => write!(f, "{} ↻ {}", self.from, self.to),
         }
     }
 }
@@ -45,14 +49,18 @@ pub enum Direction {
     Both,
 }
 
-#[allow(dead_code)]
 #[derive(PartialEq, Eq, Debug, Clone, PartialOrd, Ord, Copy)]
 pub enum DependencyType {
     Implication,
     Equivalence,
+    /// Exactly one of the activities occurs in every trace.
     NegatedEquivalence,
+    /// The activities do not occur together in a trace, but may both be absent.
     Nand,
+    /// At least one of the activities occurs in every trace (possibly both).
     Or,
+    /// Self-loop (`from == to`): the activity occurs more than once in a trace.
+    Repetition,
 }
 
 impl std::fmt::Display for DependencyType {
@@ -63,60 +71,260 @@ impl std::fmt::Display for DependencyType {
             DependencyType::NegatedEquivalence => write!(f, "⇎"),
             DependencyType::Nand => write!(f, "⊼"),
             DependencyType::Or => write!(f, "∨"),
+            DependencyType::Repetition => write!(f, "↻"),
         }
     }
 }
 
-// TODO: NAND and OR dependencies
+/// Threshold of each existential relation, see [`check_existential_dependency_with_thresholds`].
+///
+/// Noisy logs may, for example, need a stricter threshold for negated equivalence than
+/// for implication.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExistentialThresholds {
+    /// Minimum share of the traces with one activity that contain the other.
+    pub implication: f64,
+    /// Minimum share for the implications in both directions.
+    pub equivalence: f64,
+    /// Minimum share of the traces with either activity containing only one of them,
+    /// and of all traces containing one of them.
+    pub negated_equivalence: f64,
+    /// Minimum share of the traces with either activity not containing both. This is
+    /// the first condition of negated equivalence, which is checked first: NAND is
+    /// reported for the pairs that are rarely together but often both absent.
+    pub nand: f64,
+    pub or: f64,
+    pub repetition: f64,
+}
+
+impl ExistentialThresholds {
+    /// The same threshold for every relation.
+    pub fn uniform(threshold: f64) -> Self {
+        Self {
+            implication: threshold,
+            equivalence: threshold,
+            negated_equivalence: threshold,
+            nand: threshold,
+            or: threshold,
+            repetition: threshold,
+        }
+    }
+
+    fn all(&self) -> [f64; 6] {
+        [self.implication, self.equivalence, self.negated_equivalence, self.nand, self.or, self.repetition]
+    }
+}
+
+impl Default for ExistentialThresholds {
+    fn default() -> Self {
+        Self::uniform(1.0)
+    }
+}
+
 /// Checks for an existential dependency between two activities within a set of traces.
 ///
 /// This function analyzes the given traces to determine if there is an existential dependency
 /// between the `from` and `to` activities based on the specified threshold. It considers
-/// implications, equivalences, and negated equivalences to identify the type and direction
-/// of the dependency (in that order).
+/// implications, equivalences, negated equivalences, NAND (never together) and OR (at least
+/// one of them in every trace) to identify the type and direction of the dependency (in that
+/// order). Implications, negated equivalence and NAND are measured on the traces containing
+/// `from` or `to`, OR on all traces. Negated equivalence also requires one of the activities
+/// in the share of all traces given by its threshold, which separates it from NAND.
+///
+/// For a self-loop (`from == to`) it checks for repetition instead: whether the activity
+/// occurs more than once in the traces containing it.
 ///
 /// # Arguments
 ///
 /// * `from` - The name of the starting activity.
 /// * `to` - The name of the target activity.
-/// * `traces` - A vector of `Trace` objects representing the sequence of events.
+/// * `traces` - The traces of the log, either one entry per trace or trace variants
+///   weighted by their number of traces (see [`WeightedTrace`]).
 /// * `threshold` - A threshold value to determine if the dependency is significant.
 ///
 /// # Returns
 ///
 /// An `Option` containing an `ExistentialDependency` if a dependency is found, otherwise `None`.
-pub fn check_existential_dependency(
+pub fn check_existential_dependency<T: WeightedTrace>(
     from: &str,
     to: &str,
-    traces: &[Vec<&str>],
+    traces: &[T],
     threshold: f64,
+) -> Option<ExistentialDependency> {
+    check_existential_dependency_with_thresholds(from, to, traces, &ExistentialThresholds::uniform(threshold))
+}
+
+/// Works like [`check_existential_dependency`] with a separate threshold for each relation.
+///
+/// Equivalence requires both implications to reach the equivalence threshold. Otherwise an
+/// implication is reported if it reaches the implication threshold (the stronger direction if
+/// both do).
+pub fn check_existential_dependency_with_thresholds<T: WeightedTrace>(
+    from: &str,
+    to: &str,
+    traces: &[T],
+    thresholds: &ExistentialThresholds,
 ) -> Option<ExistentialDependency> {
     assert!(
-        (0.0..=1.0).contains(&threshold),
+        thresholds.all().iter().all(|threshold| (0.0..=1.0).contains(threshold)),
         "Threshold must be between 0 and 1"
     );
 
+    let counts = cooccurrence_counts(from, to, traces);
+    if from == to {
+        return classify_repetition(from, counts.from, counts.repeated, thresholds.repetition);
+    }
+
     // Prevent issues with empty traces or traces without activities
-    let relevant_traces: Vec<_> = traces
-        .iter()
-        .filter(|trace| trace.contains(&from) || trace.contains(&to))
-        .collect();
+    if counts.relevant() == 0 && !traces.is_empty() { // if traces exist, but none are relevant
+        return None;
+    }
+
+    dependency_from_counts(from, to, &counts, thresholds)
+}
+
+/// Number of traces (weighted) that contain `from`, `to` and both of them, out of `total`.
+///
+/// These are the observations an [`ExistentialDependency`] is decided on: the ratios of
+/// the candidate relations show how clearly each of them reached the threshold.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CooccurrenceCounts {
+    pub from: usize,
+    pub to: usize,
+    pub both: usize,
+    pub total: usize,
+    /// Traces containing `from` more than once (the evidence for a self-loop).
+    pub repeated: usize,
+}
+
+impl CooccurrenceCounts {
+    /// Traces containing `from` or `to`.
+    pub fn relevant(&self) -> usize {
+        self.from + self.to - self.both
+    }
+
+    /// Share of the traces with `from` that also contain `to` (`from` => `to`).
+    pub fn implication_ratio(&self) -> Option<f64> {
+        ratio(self.both, self.from)
+    }
+
+    /// Share of the traces with `to` that also contain `from` (`to` => `from`).
+    pub fn reverse_implication_ratio(&self) -> Option<f64> {
+        ratio(self.both, self.to)
+    }
+
+    /// Share of the relevant traces containing only one of the activities.
+    pub fn negated_equivalence_ratio(&self) -> Option<f64> {
+        ratio(self.relevant() - self.both, self.relevant())
+    }
+
+    /// Share of the relevant traces not containing both activities. This is the
+    /// [`CooccurrenceCounts::negated_equivalence_ratio`]: the relations differ in the
+    /// traces containing neither activity, see [`CooccurrenceCounts::or_ratio`].
+    pub fn nand_ratio(&self) -> Option<f64> {
+        ratio(self.relevant() - self.both, self.relevant())
+    }
+
+    /// Share of all traces containing at least one of the activities.
+    pub fn or_ratio(&self) -> Option<f64> {
+        ratio(self.relevant(), self.total)
+    }
+
+    /// Share of the traces with `from` containing it more than once.
+    pub fn repetition_ratio(&self) -> Option<f64> {
+        ratio(self.repeated, self.from)
+    }
+}
+
+fn ratio(count: usize, total: usize) -> Option<f64> {
+    (total > 0).then(|| count as f64 / total as f64)
+}
+
+/// Counts the traces containing `from`, `to` and both of them, which
+/// [`check_existential_dependency`] decides on.
+pub fn cooccurrence_counts<T: WeightedTrace>(from: &str, to: &str, traces: &[T]) -> CooccurrenceCounts {
+    let mut counts = CooccurrenceCounts::default();
+    for trace in traces {
+        let from_count = trace.activities().iter().filter(|&&activity| activity == from).count();
+        let contains_to = trace.activities().contains(&to);
+        counts.total += trace.weight();
+        if from_count > 0 {
+            counts.from += trace.weight();
+        }
+        if contains_to {
+            counts.to += trace.weight();
+        }
+        if from_count > 0 && contains_to {
+            counts.both += trace.weight();
+        }
+        if from_count > 1 {
+            counts.repeated += trace.weight();
+        }
+    }
+    counts
+}
+
+/// Counterpart of [`check_existential_dependency`] for precomputed co-occurrence counts.
+pub(crate) fn classify_cooccurrence(
+    from: &str,
+    to: &str,
+    counts: &CooccurrenceCounts,
+    thresholds: &ExistentialThresholds,
+) -> Option<ExistentialDependency> {
+    if counts.relevant() == 0 {
+        return None;
+    }
+
+    dependency_from_counts(from, to, counts, thresholds)
+}
 
-    if relevant_traces.is_empty() && !traces.is_empty() { // if traces exist, but none are relevant
+/// Repetition (self-loop) of `activity`: it occurs more than once in enough of the
+/// `traces_with_count` traces containing it.
+pub(crate) fn classify_repetition(
+    activity: &str,
+    traces_with_count: usize,
+    repeated_count: usize,
+    threshold: f64,
+) -> Option<ExistentialDependency> {
+    if traces_with_count == 0 || (repeated_count as f64 / traces_with_count as f64) < threshold {
         return None;
     }
+    Some(ExistentialDependency::new(activity, activity, DependencyType::Repetition, Direction::Both))
+}
 
-    let from_implies_to = has_implication(from, to, traces, threshold);
-    let to_implies_from = has_implication(to, from, traces, threshold);
+/// A relation holds if its ratio reaches the threshold, or vacuously if there was nothing
+/// to observe (e.g. `from` => `to` if `from` never occurs).
+fn holds(ratio: Option<f64>, threshold: f64) -> bool {
+    ratio.is_none_or(|ratio| ratio >= threshold)
+}
 
-    if from_implies_to && to_implies_from {
+/// Picks the dependency from the implications in both directions, falling back to
+/// negated equivalence, NAND and OR (in that order) if there is none.
+fn dependency_from_counts(
+    from: &str,
+    to: &str,
+    counts: &CooccurrenceCounts,
+    thresholds: &ExistentialThresholds,
+) -> Option<ExistentialDependency> {
+    // `from` => `to`: `to` occurs in the traces containing `from`, and vice versa
+    let forward_ratio = counts.implication_ratio();
+    let backward_ratio = counts.reverse_implication_ratio();
+
+    if holds(forward_ratio, thresholds.equivalence) && holds(backward_ratio, thresholds.equivalence) {
         return Some(ExistentialDependency {
             from: from.to_string(),
             to: to.to_string(),
             dependency_type: DependencyType::Equivalence,
             direction: Direction::Both, // Equivalence is inherently bidirectional
         });
-    } else if from_implies_to {
+    }
+
+    let from_implies_to = holds(forward_ratio, thresholds.implication);
+    let to_implies_from = holds(backward_ratio, thresholds.implication);
+    // Both can only hold below the equivalence threshold: report the stronger one
+    let forward_is_stronger = forward_ratio.unwrap_or(1.0) >= backward_ratio.unwrap_or(1.0);
+    if from_implies_to && (!to_implies_from || forward_is_stronger) {
         return Some(ExistentialDependency {
             from: from.to_string(),
             to: to.to_string(),
@@ -135,7 +343,10 @@ pub fn check_existential_dependency(
     // Check for Negated Equivalence if no implication/equivalence was found
     // Negated Equivalence: (A and not B) or (not A and B)
     // This means if A exists, B must not, AND if B exists, A must not, within traces containing either.
-    if negated_equivalence(from, to, traces, threshold) {
+    // One of them also has to occur in the traces, otherwise they are only never together (NAND)
+    if holds(counts.negated_equivalence_ratio(), thresholds.negated_equivalence)
+        && holds(counts.or_ratio(), thresholds.negated_equivalence)
+    {
         return Some(ExistentialDependency {
             from: from.to_string(),
             to: to.to_string(),
@@ -143,64 +354,18 @@ pub fn check_existential_dependency(
             direction: Direction::Both,
         });
     }
-    
-    None
-}
 
-/// Checks if there is an implication relationship between two events within a set of event traces.
-/// `from` => `to`
-/// # Parameters
-/// - `from`: The event that implies the occurrence of another event.
-/// - `to`: The event that is implied by the occurrence of the `from` event.
-/// - `event_names`: A vector of vectors, where each inner vector represents a sequence of event names (a trace).
-/// - `threshold`: A threshold value between 0 and 1 that determines the minimum proportion of valid traces required to confirm the implication.
-///
-/// # Returns
-/// - `true` if the proportion of valid traces (traces where `from` appears must also contain `to`) is greater than or equal to the threshold.
-/// - `false` otherwise.
-fn has_implication(from: &str, to: &str, event_names: &[Vec<&str>], threshold: f64) -> bool {
-    let traces_with_from: Vec<_> = event_names.iter().filter(|trace| trace.contains(&from)).collect();
-
-    if traces_with_from.is_empty() {
-        return true;
+    // Not together within the traces containing either activity, many traces contain neither
+    if holds(counts.nand_ratio(), thresholds.nand) {
+        return Some(ExistentialDependency::new(from, to, DependencyType::Nand, Direction::Both));
     }
-
-    let valid_traces_count = traces_with_from
-        .iter()
-        .filter(|trace| trace.contains(&to))
-        .count();
-    
-    (valid_traces_count as f64 / traces_with_from.len() as f64) >= threshold
-}
-
-
-/// Checks for negated equivalence.
-/// (A and not B) or (not A and B) -- meaning they don't appear together.
-/// This is checked over traces that contain *at least one* of A or B.
-fn negated_equivalence(from: &str, to: &str, event_names: &[Vec<&str>], threshold: f64) -> bool {
-    let relevant_traces: Vec<_> = event_names
-        .iter()
-        .filter(|trace| trace.contains(&from) || trace.contains(&to))
-        .collect();
-
-    if relevant_traces.is_empty() {
-        return true;
+    if holds(counts.or_ratio(), thresholds.or) {
+        return Some(ExistentialDependency::new(from, to, DependencyType::Or, Direction::Both));
     }
 
-    let valid_traces_count = relevant_traces
-        .iter()
-        .filter(|trace| {
-            let from_present = trace.contains(&from);
-            let to_present = trace.contains(&to);
-            // Valid if (from is present AND to is NOT present) OR (from is NOT present AND to IS present)
-            (from_present && !to_present) || (!from_present && to_present)
-        })
-        .count();
-
-    (valid_traces_count as f64 / relevant_traces.len() as f64) >= threshold
+    None
 }
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,15 +381,16 @@ mod tests {
         // Traces with A: ["A", "B"], ["A", "C"], ["A", "B", "D"] (3 traces)
         // Traces with A and B: ["A", "B"], ["A", "B", "D"] (2 traces)
         // Ratio: 2/3 = 0.666...
-        assert!(has_implication("A", "B", &traces, 0.6));
-        assert!(!has_implication("A", "B", &traces, 0.7));
+        let implication_ratio = cooccurrence_counts("A", "B", &traces).implication_ratio();
+        assert!(holds(implication_ratio, 0.6));
+        assert!(!holds(implication_ratio, 0.7));
     }
     
     #[test]
     fn test_has_implication_from_not_present() {
         let traces = vec![vec!["X", "Y"], vec!["Z"]];
         // 'A' is never present. traces_with_from is empty.
-        assert!(has_implication("A", "B", &traces, 1.0));
+        assert!(holds(cooccurrence_counts("A", "B", &traces).implication_ratio(), 1.0));
     }
 
     #[test]
@@ -277,8 +443,132 @@ mod tests {
         let dep = check_existential_dependency("A", "B", &traces, 0.6);
          assert_eq!(dep, Some(ExistentialDependency::new("A", "B", DependencyType::NegatedEquivalence, Direction::Both)));
 
-        // If threshold is higher, e.g., 0.7, NE should fail
+        // If threshold is higher, e.g., 0.7, NE should fail, and NAND with the same ratio
+        // OR still holds: 3 of all 4 traces contain A or B
         let dep_higher_thresh = check_existential_dependency("A", "B", &traces, 0.7);
-        assert_eq!(dep_higher_thresh, None);
+        assert_eq!(dep_higher_thresh, Some(ExistentialDependency::new("A", "B", DependencyType::Or, Direction::Both)));
+
+        // OR fails above 0.75
+        let dep_highest_thresh = check_existential_dependency("A", "B", &traces, 0.8);
+        assert_eq!(dep_highest_thresh, None);
+    }
+
+    #[test]
+    fn test_check_existential_dependency_nand() {
+        let mut traces = vec![vec!["A", "C"], vec!["B", "C"], vec!["A", "C"], vec!["B", "C"], vec!["A", "B", "C"]];
+        traces.extend(vec![vec!["C"]; 5]);
+        // A->B and B->A: 1/3. NAND over the 5 relevant traces: together only in the last one, 4/5
+        // NE fails as only 5 of all 10 traces contain A or B
+        let dep = check_existential_dependency("A", "B", &traces, 0.8);
+        assert_eq!(dep, Some(ExistentialDependency::new("A", "B", DependencyType::Nand, Direction::Both)));
+        assert_eq!(check_existential_dependency("A", "B", &traces, 0.9), None);
+    }
+
+    #[test]
+    fn test_check_existential_dependency_nand_at_default_thresholds() {
+        let thresholds = ExistentialThresholds::default();
+        // Never together and both absent in one trace: NAND
+        let traces = vec![vec!["A", "C"], vec!["B", "C"], vec!["C"]];
+        assert_eq!(
+            check_existential_dependency_with_thresholds("A", "B", &traces, &thresholds),
+            Some(ExistentialDependency::new("A", "B", DependencyType::Nand, Direction::Both))
+        );
+
+        // Never together and one of them in every trace: negated equivalence
+        let traces = vec![vec!["A", "C"], vec!["B", "C"], vec!["A"]];
+        assert_eq!(
+            check_existential_dependency_with_thresholds("A", "B", &traces, &thresholds),
+            Some(ExistentialDependency::new("A", "B", DependencyType::NegatedEquivalence, Direction::Both))
+        );
+
+        // Together once: neither holds
+        let traces = vec![vec!["A", "C"], vec!["B", "C"], vec!["C"], vec!["A", "B"]];
+        assert_eq!(check_existential_dependency_with_thresholds("A", "B", &traces, &thresholds), None);
+    }
+
+    #[test]
+    fn test_check_existential_dependency_or() {
+        let traces = vec![vec!["A"], vec!["B"], vec!["A", "B"], vec!["A", "B"]];
+        // Neither implies the other (2/3), NE and NAND hold for 2/4 traces only,
+        // but every trace contains A or B
+        let dep = check_existential_dependency("A", "B", &traces, 1.0);
+        assert_eq!(dep, Some(ExistentialDependency::new("A", "B", DependencyType::Or, Direction::Both)));
+
+        let traces = vec![vec!["A"], vec!["B"], vec!["A", "B"], vec!["A", "B"], vec!["C"]];
+        assert_eq!(check_existential_dependency("A", "B", &traces, 0.8), Some(ExistentialDependency::new("A", "B", DependencyType::Or, Direction::Both)));
+        assert_eq!(check_existential_dependency("A", "B", &traces, 0.9), None);
+    }
+
+    #[test]
+    fn test_check_existential_dependency_weighted_variants() {
+        let traces = vec![vec!["A", "B"], vec!["A"], vec!["A", "B"], vec!["A", "B"]];
+        let variants = vec![(vec!["A", "B"], 3), (vec!["A"], 1)];
+        // A->B holds in 3 of 4 traces, which the variants have to reflect
+        for threshold in [0.7, 0.8] {
+            assert_eq!(
+                check_existential_dependency("A", "B", &variants, threshold),
+                check_existential_dependency("A", "B", &traces, threshold)
+            );
+        }
+        assert_eq!(
+            check_existential_dependency("A", "B", &variants, 0.75),
+            Some(ExistentialDependency::new("A", "B", DependencyType::Equivalence, Direction::Both))
+        );
+    }
+
+    #[test]
+    fn test_check_existential_dependency_with_thresholds() {
+        let traces = vec![vec!["A", "B"], vec!["A", "B"], vec!["A", "B"], vec!["A"], vec!["B"], vec!["A", "B"]];
+        // A->B and B->A: 4/5, NE: 2/6
+        let thresholds = ExistentialThresholds { equivalence: 0.9, implication: 0.8, ..ExistentialThresholds::uniform(0.8) };
+        assert_eq!(
+            check_existential_dependency_with_thresholds("A", "B", &traces, &thresholds),
+            Some(ExistentialDependency::new("A", "B", DependencyType::Implication, Direction::Forward))
+        );
+        assert_eq!(
+            check_existential_dependency("A", "B", &traces, 0.8),
+            Some(ExistentialDependency::new("A", "B", DependencyType::Equivalence, Direction::Both))
+        );
+
+        // NE holds for 4/6 relevant traces: only with a lenient negated equivalence threshold
+        let traces = vec![vec!["A"], vec!["A"], vec!["B"], vec!["B"], vec!["A", "B"], vec!["A", "B"]];
+        let thresholds = ExistentialThresholds { negated_equivalence: 0.6, ..ExistentialThresholds::uniform(0.9) };
+        assert_eq!(
+            check_existential_dependency_with_thresholds("A", "B", &traces, &thresholds),
+            Some(ExistentialDependency::new("A", "B", DependencyType::NegatedEquivalence, Direction::Both))
+        );
+        let thresholds = ExistentialThresholds { negated_equivalence: 0.9, or: 1.0, ..ExistentialThresholds::uniform(0.6) };
+        assert_eq!(
+            check_existential_dependency_with_thresholds("A", "B", &traces, &thresholds),
+            Some(ExistentialDependency::new("A", "B", DependencyType::Nand, Direction::Both))
+        );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_cooccurrence_counts_ratios() {
+        let traces = vec![(vec!["A", "B", "A"], 2), (vec!["A"], 1), (vec!["B"], 1), (vec!["C"], 4)];
+        let counts = cooccurrence_counts("A", "B", &traces);
+        assert_eq!(counts, CooccurrenceCounts { from: 3, to: 3, both: 2, total: 8, repeated: 2 });
+        assert_eq!(counts.relevant(), 4);
+        assert_eq!(counts.implication_ratio(), Some(2.0 / 3.0));
+        assert_eq!(counts.negated_equivalence_ratio(), Some(0.5));
+        assert_eq!(counts.nand_ratio(), Some(0.5));
+        assert_eq!(counts.or_ratio(), Some(0.5));
+        assert_eq!(counts.repetition_ratio(), Some(2.0 / 3.0));
+        // Nothing to observe for activities that never occur
+        assert_eq!(cooccurrence_counts("X", "Y", &traces).implication_ratio(), None);
+    }
+
+    #[test]
+    fn test_check_existential_dependency_repetition() {
+        let traces = vec![vec!["A", "B", "A"], vec!["A", "B"], vec!["B"], vec!["A", "A", "A"]];
+        // A occurs more than once in 2 of the 3 traces containing it, B never repeats
+        assert_eq!(
+            check_existential_dependency("A", "A", &traces, 0.6),
+            Some(ExistentialDependency::new("A", "A", DependencyType::Repetition, Direction::Both))
+        );
+        assert_eq!(check_existential_dependency("A", "A", &traces, 0.7), None);
+        assert_eq!(check_existential_dependency("B", "B", &traces, 0.1), None);
+        assert_eq!(check_existential_dependency("C", "C", &traces, 0.1), None);
+    }
+}
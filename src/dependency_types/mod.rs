@@ -1,3 +1,34 @@
 pub mod dependency;
 pub mod existential;
-pub mod temporal;
\ No newline at end of file
+pub mod temporal;
+
+/// A trace as seen by the dependency checkers: its activities and the number of
+/// traces of the log it stands for.
+///
+/// Plain traces (`Vec<&str>`) count once, trace variants (`(Vec<&str>, usize)`, see
+/// [`crate::TraceView::trace_variants`]) count as often as they occur, so the checkers
+/// give the same results on a log and on its variants.
+pub trait WeightedTrace {
+    fn activities(&self) -> &[&str];
+    fn weight(&self) -> usize;
+}
+
+impl WeightedTrace for Vec<&str> {
+    fn activities(&self) -> &[&str] {
+        self
+    }
+
+    fn weight(&self) -> usize {
+        1
+    }
+}
+
+impl WeightedTrace for (Vec<&str>, usize) {
+    fn activities(&self) -> &[&str] {
+        &self.0
+    }
+
+    fn weight(&self) -> usize {
+        self.1
+    }
+}
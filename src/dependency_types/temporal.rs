@@ -1,4 +1,8 @@
+use super::WeightedTrace;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct TemporalDependency {
@@ -29,10 +33,12 @@ impl std::fmt::Display for TemporalDependency {
         let type_char = match self.dependency_type {
             DependencyType::Direct => "d",
             DependencyType::Eventual => "e",
+            DependencyType::Parallel => return write!(f, "{} ∥ {}", self.from, self.to),
         };
         match &self.direction {
             Direction::Forward => write!(f, "{} ≺{} {}", self.from, type_char, self.to),
             Direction::Backward => write!(f, "{} ≻{} {}", self.from, type_char, self.to),
+            Direction::Both => write!(f, "{} ≺{}≻ {}", self.from, type_char, self.to),
         }
     }
 }
@@ -41,12 +47,17 @@ impl std::fmt::Display for TemporalDependency {
 pub enum Direction {
     Forward,
     Backward,
+    /// Both orders, see [`DependencyType::Parallel`].
+    Both,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Copy)]
 pub enum DependencyType {
     Direct,
     Eventual,
+    /// Both orders are observed frequently, as for concurrent or interleaved
+    /// activities. Always has [`Direction::Both`].
+    Parallel,
 }
 
 impl std::fmt::Display for DependencyType {
@@ -54,40 +65,517 @@ impl std::fmt::Display for DependencyType {
         match self {
             DependencyType::Direct => write!(f, "d"),
             DependencyType::Eventual => write!(f, "e"),
+            DependencyType::Parallel => write!(f, "p"),
         }
     }
 }
 
+/// An activity occurrence spanning a time interval (e.g. from its `start` to its
+/// `complete` lifecycle transition), as used by [`check_interval_temporal_dependency`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval<'a> {
+    pub activity: &'a str,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl Interval<'_> {
+    /// Returns `true` if `self` is finished before `other` starts.
+    /// Intervals that only touch are ordered, unless both are instants at the same time.
+    fn precedes(&self, other: &Interval) -> bool {
+        self.end < other.start
+            || (self.end == other.start && (self.start < self.end || other.start < other.end))
+    }
+}
+
+/// How the observations of one direction decide whether it is a
+/// [`DependencyType::Direct`] or an [`DependencyType::Eventual`] relation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TypeAggregation {
+    /// Eventual as soon as one observation is eventual.
+    #[default]
+    AnyEventual,
+    /// Direct if more observations are direct than eventual.
+    Majority,
+    /// Direct if at least this share of the observations is direct.
+    DirectRatio(f64),
+}
+
+impl TypeAggregation {
+    /// The type of a direction with `direct` direct and `eventual` eventual observations.
+    fn dependency_type(&self, direct: usize, eventual: usize) -> DependencyType {
+        let is_direct = match *self {
+            TypeAggregation::AnyEventual => eventual == 0,
+            TypeAggregation::Majority => direct > eventual,
+            TypeAggregation::DirectRatio(ratio) => {
+                direct as f64 >= ratio * (direct + eventual) as f64
+            }
+        };
+        if is_direct {
+            DependencyType::Direct
+        } else {
+            DependencyType::Eventual
+        }
+    }
+}
+
+impl std::fmt::Display for TypeAggregation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TypeAggregation::AnyEventual => write!(f, "any-eventual"),
+            TypeAggregation::Majority => write!(f, "majority"),
+            TypeAggregation::DirectRatio(ratio) => write!(f, "direct-ratio={}", ratio),
+        }
+    }
+}
+
+impl std::str::FromStr for TypeAggregation {
+    type Err = String;
+
+    /// Parses the [`Display`](std::fmt::Display) form, e.g. `majority` or `direct-ratio=0.9`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "any-eventual" => Ok(TypeAggregation::AnyEventual),
+            "majority" => Ok(TypeAggregation::Majority),
+            _ => s
+                .strip_prefix("direct-ratio=")
+                .and_then(|ratio| ratio.parse().ok())
+                .map(TypeAggregation::DirectRatio)
+                .ok_or_else(|| {
+                    format!(
+                        "unknown aggregation '{}', expected any-eventual, majority or direct-ratio=<ratio>",
+                        s
+                    )
+                }),
+        }
+    }
+}
+
+/// Thresholds of the temporal relations by their type: a direction counts as
+/// [`DependencyType::Direct`] or [`DependencyType::Eventual`] by its `type_aggregation`,
+/// and must reach the `direct` respectively the `eventual` threshold.
+///
+/// If neither direction is clear and a `parallel` threshold is set, the pair is
+/// [`DependencyType::Parallel`] when both orders reach it. Without one (the default),
+/// such pairs have no temporal dependency.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TemporalThresholds {
+    pub direct: f64,
+    pub eventual: f64,
+    pub parallel: Option<f64>,
+    pub type_aggregation: TypeAggregation,
+}
+
+impl TemporalThresholds {
+    /// The same threshold for direct and eventual relations, without parallel relations.
+    pub fn uniform(threshold: f64) -> Self {
+        Self {
+            direct: threshold,
+            eventual: threshold,
+            parallel: None,
+            type_aggregation: TypeAggregation::AnyEventual,
+        }
+    }
+
+    fn threshold(&self, dependency_type: DependencyType) -> f64 {
+        match dependency_type {
+            DependencyType::Direct => self.direct,
+            DependencyType::Eventual => self.eventual,
+            // No ratio reaches a missing threshold
+            DependencyType::Parallel => self.parallel.unwrap_or(f64::INFINITY),
+        }
+    }
+}
+
+impl Default for TemporalThresholds {
+    fn default() -> Self {
+        Self::uniform(1.0)
+    }
+}
+
 /// Checks for temporal dependencies between two activities across multiple traces.
 ///
 /// # Parameters
 /// - `from`: The starting activity in the dependency.
 /// - `to`: The ending activity in the dependency.
 /// - `traces`: A list of traces where each trace is an ordered sequence of activities.
+///   Trace variants count as often as they occur (see [`WeightedTrace`]).
 /// - `threshold`: The ratio threshold for considering the dependency direction.
-///    (for example, a threshold of 0.8 would mean that the dependency would be considered
-///    a Direct dependency if it is found in at least 80% of the traces)
+///   (for example, a threshold of 0.8 would mean that the dependency would be considered
+///   a Direct dependency if it is found in at least 80% of the traces)
 ///
 /// # Returns
 /// An `Option` containing the `TemporalDependency` if a dependency is found; otherwise, `None`.
-pub fn check_temporal_dependency(
+/// Parallel relations are only reported by [`check_temporal_dependency_with_thresholds`]
+/// with a `parallel` threshold.
+pub fn check_temporal_dependency<T: WeightedTrace>(
     from: &str,
     to: &str,
-    traces: &[Vec<&str>],
+    traces: &[T],
     threshold: f64,
 ) -> Option<TemporalDependency> {
-    let mut all_observed_relations: Vec<(DependencyType, Direction)> = Vec::new();
+    check_temporal_dependency_with_thresholds(from, to, traces, &TemporalThresholds::uniform(threshold))
+}
+
+/// Works like [`check_temporal_dependency`] with separate thresholds for direct and
+/// eventual relations.
+pub fn check_temporal_dependency_with_thresholds<T: WeightedTrace>(
+    from: &str,
+    to: &str,
+    traces: &[T],
+    thresholds: &TemporalThresholds,
+) -> Option<TemporalDependency> {
+    classify_dependencies(from, to, &temporal_relation_counts(from, to, traces), thresholds)
+}
+
+/// Counts the relations between two activities across multiple traces that
+/// [`check_temporal_dependency`] decides on.
+pub fn temporal_relation_counts<T: WeightedTrace>(from: &str, to: &str, traces: &[T]) -> RelationCounts {
+    let mut all_observed_relations = RelationCounts::default();
 
     for trace in traces {
-        let trace_relations = check_trace_dependency_pairs(from, to, trace);
-        all_observed_relations.extend(trace_relations);
+        for relation in check_trace_dependency_pairs(from, to, trace.activities()) {
+            all_observed_relations.add(relation, trace.weight());
+        }
     }
-    
-    if all_observed_relations.is_empty() {
-        return None;
+
+    all_observed_relations
+}
+
+/// Checks for temporal dependencies between two activities whose occurrences span time intervals.
+///
+/// Works like [`check_temporal_dependency`], but an occurrence of `from` only precedes an
+/// occurrence of `to` if it is finished before the other one starts. Overlapping occurrences
+/// were executed concurrently: they count as observations without any direction, so
+/// activities that frequently overlap do not reach the threshold in either direction.
+///
+/// # Parameters
+/// - `traces`: The activity occurrences of each trace, ordered by their start.
+pub fn check_interval_temporal_dependency(
+    from: &str,
+    to: &str,
+    traces: &[Vec<Interval>],
+    threshold: f64,
+) -> Option<TemporalDependency> {
+    classify_dependencies(
+        from,
+        to,
+        &interval_relation_counts(from, to, traces),
+        &TemporalThresholds::uniform(threshold),
+    )
+}
+
+/// Counts the relations between two activities whose occurrences span time intervals
+/// that [`check_interval_temporal_dependency`] decides on.
+pub fn interval_relation_counts(from: &str, to: &str, traces: &[Vec<Interval>]) -> RelationCounts {
+    interval_observations(from, to, traces).0
+}
+
+/// The time gaps of the ordered relations between two activities whose occurrences span
+/// time intervals, `None` if they are never ordered.
+pub fn interval_time_gaps(from: &str, to: &str, traces: &[Vec<Interval>]) -> Option<TimeGaps> {
+    interval_observations(from, to, traces).1
+}
+
+/// The relation counts and time gaps of [`interval_relation_counts`] and
+/// [`interval_time_gaps`] in one pass.
+fn interval_observations(
+    from: &str,
+    to: &str,
+    traces: &[Vec<Interval>],
+) -> (RelationCounts, Option<TimeGaps>) {
+    let mut all_observed_relations = RelationCounts::default();
+    let mut gaps = Vec::new();
+
+    for trace in traces {
+        let (trace_relations, trace_unordered) = check_trace_interval_pairs(from, to, trace);
+        for (relation, gap) in trace_relations {
+            all_observed_relations.add(relation, 1);
+            gaps.push(gap);
+        }
+        all_observed_relations.unordered += trace_unordered;
+    }
+
+    (all_observed_relations, TimeGaps::from_gaps(gaps))
+}
+
+/// Time between the related occurrences of two activities, from the end of the earlier
+/// to the start of the later one, over all ordered observations (in either direction).
+///
+/// Small gaps indicate tightly coupled steps, large or widely spread gaps loosely
+/// coupled ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimeGaps {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+    /// Number of ordered observations.
+    pub count: usize,
+}
+
+impl TimeGaps {
+    /// The statistics of `gaps`, `None` if there are none. The median of an even number
+    /// of gaps is the mean of the two middle ones.
+    pub fn from_gaps(mut gaps: Vec<Duration>) -> Option<Self> {
+        gaps.sort();
+        let count = gaps.len();
+        let median = match count {
+            0 => return None,
+            _ if count % 2 == 1 => gaps[count / 2],
+            _ => (gaps[count / 2 - 1] + gaps[count / 2]) / 2,
+        };
+        Some(Self {
+            min: gaps[0],
+            median,
+            max: gaps[count - 1],
+            count,
+        })
+    }
+}
+
+/// Seconds of a time gap, with millisecond precision.
+pub fn duration_seconds(duration: Duration) -> f64 {
+    duration.num_milliseconds() as f64 / 1000.0
+}
+
+impl std::fmt::Display for TimeGaps {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "min {}s, median {}s, max {}s",
+            duration_seconds(self.min),
+            duration_seconds(self.median),
+            duration_seconds(self.max)
+        )
+    }
+}
+
+/// Number of observed relations between two activities, by type and direction.
+///
+/// These are the observations a [`TemporalDependency`] is decided on: the ratios show
+/// how clearly one direction reached the threshold.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RelationCounts {
+    pub forward_direct: usize,
+    pub forward_eventual: usize,
+    pub backward_direct: usize,
+    pub backward_eventual: usize,
+    /// Observations without any order (concurrent occurrences).
+    pub unordered: usize,
+}
+
+impl RelationCounts {
+    pub(crate) fn add(&mut self, (dependency_type, direction): (DependencyType, Direction), count: usize) {
+        let counter = match (direction, dependency_type) {
+            (Direction::Forward, DependencyType::Direct) => &mut self.forward_direct,
+            (Direction::Forward, DependencyType::Eventual) => &mut self.forward_eventual,
+            (Direction::Backward, DependencyType::Direct) => &mut self.backward_direct,
+            (Direction::Backward, DependencyType::Eventual) => &mut self.backward_eventual,
+            // Single observations are ordered, an observation of both orders has none
+            (Direction::Both, _) | (_, DependencyType::Parallel) => &mut self.unordered,
+        };
+        *counter += count;
+    }
+
+    pub fn forward(&self) -> usize {
+        self.forward_direct + self.forward_eventual
+    }
+
+    pub fn backward(&self) -> usize {
+        self.backward_direct + self.backward_eventual
+    }
+
+    /// All observations, ordered or not.
+    pub fn total(&self) -> usize {
+        self.forward() + self.backward() + self.unordered
+    }
+
+    /// Share of the observations in which `from` precedes `to`, `None` without observations.
+    pub fn forward_ratio(&self) -> Option<f64> {
+        (self.total() > 0).then(|| self.forward() as f64 / self.total() as f64)
+    }
+
+    /// Share of the observations in which `to` precedes `from`, `None` without observations.
+    pub fn backward_ratio(&self) -> Option<f64> {
+        (self.total() > 0).then(|| self.backward() as f64 / self.total() as f64)
+    }
+}
+
+impl FromIterator<(DependencyType, Direction)> for RelationCounts {
+    fn from_iter<I: IntoIterator<Item = (DependencyType, Direction)>>(relations: I) -> Self {
+        let mut counts = RelationCounts::default();
+        for relation in relations {
+            counts.add(relation, 1);
+        }
+        counts
+    }
+}
+
+/// An ordered relation observed between two occurrences, with the time between them.
+pub(crate) type TimedRelation = ((DependencyType, Direction), Duration);
+
+/// Interval counterpart of `check_trace_dependency_pairs`. Returns the ordered relations
+/// with their time gap and the number of `from`/`to` pairs that overlap in time.
+fn check_trace_interval_pairs(
+    from_activity: &str,
+    to_activity: &str,
+    trace: &[Interval],
+) -> (Vec<TimedRelation>, usize) {
+    // `first` precedes `second` directly if no other occurrence lies completely in between
+    let relation_type = |first: usize, second: usize| {
+        let is_direct = !trace
+            .iter()
+            .any(|other| trace[first].precedes(other) && other.precedes(&trace[second]));
+        if is_direct {
+            DependencyType::Direct
+        } else {
+            DependencyType::Eventual
+        }
+    };
+    let occurrences = |activity: &str| -> Vec<usize> {
+        (0..trace.len())
+            .filter(|&i| trace[i].activity == activity)
+            .collect()
+    };
+
+    let mut observed_relations = Vec::new();
+    let mut unordered_count = 0;
+    interval_pair_relations(
+        trace,
+        &occurrences(from_activity),
+        &occurrences(to_activity),
+        from_activity == to_activity,
+        relation_type,
+        |relation| match relation {
+            Some(relation) => observed_relations.push(relation),
+            None => unordered_count += 1,
+        },
+    );
+    (observed_relations, unordered_count)
+}
+
+/// Observes the interval relations of every ordered pair of activities occurring in
+/// `trace` in one pass: `observe(from, to, relation)` is called for every observation,
+/// with `None` for occurrences that overlap in time. Self-loops are only observed with
+/// `include_self_loops`.
+///
+/// Gives the same observations as [`check_trace_interval_pairs`] for each pair.
+pub(crate) fn trace_interval_observations<'a>(
+    trace: &[Interval<'a>],
+    include_self_loops: bool,
+    mut observe: impl FnMut(&'a str, &'a str, Option<TimedRelation>),
+) {
+    let mut occurrences: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (i, interval) in trace.iter().enumerate() {
+        occurrences.entry(interval.activity).or_default().push(i);
+    }
+
+    // Per occurrence: the earliest end of the occurrences it precedes, and whether one
+    // of the occurrences ending then is not an instant
+    let earliest_successor: Vec<Option<(DateTime<Utc>, bool)>> = trace
+        .iter()
+        .map(|first| {
+            trace
+                .iter()
+                .filter(|other| first.precedes(other))
+                .fold(None, |earliest, other| {
+                    let lasts = other.start < other.end;
+                    match earliest {
+                        Some((end, _)) if end < other.end => earliest,
+                        Some((end, lasting)) if end == other.end => Some((end, lasting || lasts)),
+                        _ => Some((other.end, lasts)),
+                    }
+                })
+        })
+        .collect();
+    // Some occurrence lies between `first` and `second` if the earliest one after `first`
+    // precedes `second`
+    let relation_type = |first: usize, second: usize| {
+        let second = &trace[second];
+        let is_eventual = earliest_successor[first].is_some_and(|(end, lasting)| {
+            end < second.start || (end == second.start && (lasting || second.start < second.end))
+        });
+        if is_eventual {
+            DependencyType::Eventual
+        } else {
+            DependencyType::Direct
+        }
+    };
+
+    for (&from, from_occurrences) in &occurrences {
+        for (&to, to_occurrences) in &occurrences {
+            if from != to || include_self_loops {
+                interval_pair_relations(
+                    trace,
+                    from_occurrences,
+                    to_occurrences,
+                    from == to,
+                    relation_type,
+                    |relation| observe(from, to, relation),
+                );
+            }
+        }
+    }
+}
+
+/// Relates the occurrences (indices into `trace`, in trace order) of two activities,
+/// or of an activity with itself for a `self_loop`. Calls `observe` with every ordered
+/// relation and its time gap, and with `None` for every pair of overlapping occurrences.
+fn interval_pair_relations(
+    trace: &[Interval],
+    from_occurrences: &[usize],
+    to_occurrences: &[usize],
+    self_loop: bool,
+    relation_type: impl Fn(usize, usize) -> DependencyType,
+    mut observe: impl FnMut(Option<TimedRelation>),
+) {
+    if self_loop {
+        // Handle self-loops: A -> A
+        for (i, &first) in from_occurrences.iter().enumerate() {
+            for &second in &from_occurrences[i + 1..] {
+                if trace[first].precedes(&trace[second]) {
+                    observe(Some((
+                        (relation_type(first, second), Direction::Forward),
+                        trace[second].start - trace[first].end,
+                    )));
+                } else {
+                    observe(None);
+                }
+            }
+        }
+        return;
     }
 
-    classify_dependencies(from, to, all_observed_relations, threshold)
+    let mut from_ptr = 0;
+    let mut to_ptr = 0;
+
+    while from_ptr < from_occurrences.len() && to_ptr < to_occurrences.len() {
+        let (from, to) = (from_occurrences[from_ptr], to_occurrences[to_ptr]);
+        let (from_interval, to_interval) = (&trace[from], &trace[to]);
+
+        if from_interval.precedes(to_interval) {
+            observe(Some((
+                (relation_type(from, to), Direction::Forward),
+                to_interval.start - from_interval.end,
+            )));
+            from_ptr += 1;
+            to_ptr += 1;
+        } else if to_interval.precedes(from_interval) {
+            observe(Some((
+                (relation_type(to, from), Direction::Backward),
+                from_interval.start - to_interval.end,
+            )));
+            // Consume only 'to' as 'from' might pair with a later 'to'
+            to_ptr += 1;
+        } else {
+            // Executed concurrently
+            observe(None);
+            from_ptr += 1;
+            to_ptr += 1;
+        }
+    }
 }
 
 
@@ -114,45 +602,46 @@ fn check_trace_dependency_pairs(
         .collect();
 
     if from_activity == to_activity {
-        // Handle self-loops: A -> A
-        for i in 0..from_indices.len() {
-            for j in (i + 1)..from_indices.len() {
-                let idx1 = from_indices[i];
-                let idx2 = from_indices[j];
-                // All self-loops are forward for A -> A
-                let dep_type = if idx2 == idx1 + 1 {
-                    DependencyType::Direct
-                } else {
-                    DependencyType::Eventual
-                };
-                observed_relations.push((dep_type, Direction::Forward));
-            }
-        }
+        self_loop_relations(&from_indices, |relation, _, _| observed_relations.push(relation));
         return observed_relations;
     }
 
-    // For each occurrence of `from_activity`, find its relation to `to_activity`
-    for &from_idx in &from_indices {
-        let mut best_forward: Option<(DependencyType, usize)> = None; // type, distance
-        let mut best_backward: Option<(DependencyType, usize)> = None; // type, distance
+    pair_position_relations(&from_indices, &to_indices, |relation, _, _| {
+        observed_relations.push(relation)
+    });
+    observed_relations
+}
 
-        for &to_idx in &to_indices {
-            if to_idx > from_idx { // Potential forward dependency
-                let distance = to_idx - from_idx;
-                let dep_type = if distance == 1 { DependencyType::Direct } else { DependencyType::Eventual };
-                if best_forward.map_or(true, |(_, d)| distance < d) {
-                    best_forward = Some((dep_type, distance));
-                }
-            } else if to_idx < from_idx { // Potential backward dependency
-                let distance = from_idx - to_idx;
-                let dep_type = if distance == 1 { DependencyType::Direct } else { DependencyType::Eventual };
-                 if best_backward.map_or(true, |(_, d)| distance < d) {
-                    best_backward = Some((dep_type, distance));
-                }
-            }
+/// Reports the relation of every pair of occurrences of one activity (self-loop A -> A),
+/// given its positions in a trace in ascending order, together with the positions of
+/// the earlier and the later occurrence.
+pub(crate) fn self_loop_relations(
+    indices: &[usize],
+    mut observe: impl FnMut((DependencyType, Direction), usize, usize),
+) {
+    for i in 0..indices.len() {
+        for j in (i + 1)..indices.len() {
+            let idx1 = indices[i];
+            let idx2 = indices[j];
+            // All self-loops are forward for A -> A
+            let dep_type = if idx2 == idx1 + 1 {
+                DependencyType::Direct
+            } else {
+                DependencyType::Eventual
+            };
+            observe((dep_type, Direction::Forward), idx1, idx2);
         }
     }
+}
 
+/// Pairs the positions of two distinct activities in a trace (both in ascending order)
+/// and reports the relation of every pair to `observe`, together with the positions of
+/// the earlier and the later occurrence.
+pub(crate) fn pair_position_relations(
+    from_indices: &[usize],
+    to_indices: &[usize],
+    mut observe: impl FnMut((DependencyType, Direction), usize, usize),
+) {
     let mut from_ptr = 0;
     let mut to_ptr = 0;
 
@@ -167,7 +656,7 @@ fn check_trace_dependency_pairs(
                 } else {
                     DependencyType::Eventual
                 };
-                observed_relations.push((dep_type, Direction::Forward));
+                observe((dep_type, Direction::Forward), from_pos, to_pos);
                 // Consume both from and to for this pair
                 from_ptr += 1;
                 to_ptr += 1; 
@@ -178,7 +667,7 @@ fn check_trace_dependency_pairs(
                 } else {
                     DependencyType::Eventual
                 };
-                observed_relations.push((dep_type, Direction::Backward));
+                observe((dep_type, Direction::Backward), to_pos, from_pos);
                 // Consume only 'to' as 'from' might pair with a later 'to'
                 to_ptr += 1;
             }
@@ -191,71 +680,93 @@ fn check_trace_dependency_pairs(
             }
         }
     }
-    observed_relations
 }
 
 
+/// Observes the time gaps of the ordered relations of every ordered pair of activities
+/// occurring in `trace`, an activity sequence with the timestamp of each event: the
+/// occurrences are paired like [`check_temporal_dependency`] does and
+/// `observe(from, to, gap)` is called for every pair of timed occurrences. Self-loops
+/// are only observed with `include_self_loops`.
+pub(crate) fn trace_time_gaps<'a>(
+    trace: &[(&'a str, Option<DateTime<Utc>>)],
+    include_self_loops: bool,
+    mut observe: impl FnMut(&'a str, &'a str, Duration),
+) {
+    let mut occurrences: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (i, (activity, _)) in trace.iter().enumerate() {
+        occurrences.entry(activity).or_default().push(i);
+    }
+
+    for (&from, from_occurrences) in &occurrences {
+        for (&to, to_occurrences) in &occurrences {
+            let mut observe_gap = |_, earlier: usize, later: usize| {
+                if let (Some(start), Some(end)) = (trace[earlier].1, trace[later].1) {
+                    observe(from, to, end - start);
+                }
+            };
+            if from != to {
+                pair_position_relations(from_occurrences, to_occurrences, observe_gap);
+            } else if include_self_loops {
+                self_loop_relations(from_occurrences, &mut observe_gap);
+            }
+        }
+    }
+}
+
 /// Classifies the dependencies based on their ratio to determine the overall dependency.
 ///
 /// # Parameters
 /// - `from`: The starting activity in the dependency.
 /// - `to`: The ending activity in the dependency.
-/// - `dependencies`: A vector of dependencies found in the traces.
-/// - `threshold`: The ratio threshold for determining the direction of the dependency.
+/// - `dependencies`: The relations found in the traces, including the unordered observations.
+/// - `thresholds`: The ratio thresholds for determining the direction of the dependency,
+///   depending on whether the relations in that direction are direct or eventual.
 ///
 /// # Returns
 /// An `Option` containing the `TemporalDependency` if a dependency direction meets the threshold; otherwise, `None`.
-fn classify_dependencies(
+pub(crate) fn classify_dependencies(
     from: &str,
     to: &str,
-    dependencies: Vec<(DependencyType, Direction)>,
-    threshold: f64,
+    dependencies: &RelationCounts,
+    thresholds: &TemporalThresholds,
 ) -> Option<TemporalDependency> {
-    if dependencies.is_empty() {
+    let forward_relations_count = dependencies.forward();
+    let backward_relations_count = dependencies.backward();
+
+    if forward_relations_count + backward_relations_count == 0 {
         return None;
     }
 
-    let total_observed_relations = dependencies.len() as f64;
-    
-    let forward_relations_count = dependencies
-        .iter()
-        .filter(|(_, dir)| *dir == Direction::Forward)
-        .count();
-    
-    let backward_relations_count = dependencies
-        .iter()
-        .filter(|(_, dir)| *dir == Direction::Backward)
-        .count();
+    let total_observed_relations =
+        (forward_relations_count + backward_relations_count + dependencies.unordered) as f64;
 
     let forward_ratio = forward_relations_count as f64 / total_observed_relations;
     let backward_ratio = backward_relations_count as f64 / total_observed_relations;
 
-    let determined_direction;
-    let relevant_dependencies_for_type_check: Vec<(DependencyType, Direction)>;
+    // The type of each direction (Direct or Eventual) is based on the relations that support it,
+    // aggregated as configured (by default, any Eventual relation makes it Eventual).
+    let aggregation = thresholds.type_aggregation;
+    let forward_type = aggregation.dependency_type(dependencies.forward_direct, dependencies.forward_eventual);
+    let backward_type = aggregation.dependency_type(dependencies.backward_direct, dependencies.backward_eventual);
+    let forward_threshold = thresholds.threshold(forward_type);
+    let backward_threshold = thresholds.threshold(backward_type);
 
-    if forward_ratio >= threshold && backward_ratio < threshold { // Clear forward
-        determined_direction = Direction::Forward;
-        relevant_dependencies_for_type_check = dependencies.into_iter().filter(|(_, dir)| *dir == Direction::Forward).collect();
-    } else if backward_ratio >= threshold && forward_ratio < threshold { // Clear backward
-        determined_direction = Direction::Backward;
-        relevant_dependencies_for_type_check = dependencies.into_iter().filter(|(_, dir)| *dir == Direction::Backward).collect();
-    } else {
-        // Ambiguous or no dominant direction above threshold
-        return None; 
-    }
-    
-    // If we have a clear direction, determine type (Direct or Eventual)
-    // Based on the relations that support the determined_direction.
-    // If any of these are Eventual, the overall is Eventual. Otherwise, it's Direct.
-    let is_eventual = relevant_dependencies_for_type_check
-        .iter()
-        .any(|(dep_type, _)| *dep_type == DependencyType::Eventual);
-
-    let final_dependency_type = if is_eventual {
-        DependencyType::Eventual
-    } else {
-        DependencyType::Direct // All supporting relations must have been Direct
-    };
+    let (determined_direction, final_dependency_type) =
+        if forward_ratio >= forward_threshold && backward_ratio < backward_threshold { // Clear forward
+            (Direction::Forward, forward_type)
+        } else if backward_ratio >= backward_threshold && forward_ratio < forward_threshold { // Clear backward
+            (Direction::Backward, backward_type)
+        } else if forward_relations_count > 0
+            && backward_relations_count > 0
+            && forward_ratio.min(backward_ratio) >= thresholds.threshold(DependencyType::Parallel)
+        {
+            // Both orders are observed and frequent: concurrent or interleaved
+            (Direction::Both, DependencyType::Parallel)
+        } else {
+            // Ambiguous or no dominant direction above threshold
+            return None;
+        };
 
     Some(TemporalDependency::new(
         from,
@@ -424,7 +935,7 @@ mod tests {
             (DependencyType::Direct, Direction::Forward)
         ]);
 
-        let dep = classify_dependencies("A", "C", relations, 0.6);
+        let dep = classify_dependencies("A", "C", &relations.into_iter().collect(), &TemporalThresholds::uniform(0.6));
          assert_eq!(
             dep,
             Some(TemporalDependency::new(
@@ -435,4 +946,247 @@ mod tests {
             ))
         );
     }
-}
\ No newline at end of file
+
+    fn interval(activity: &str, start: u32, end: u32) -> Interval<'_> {
+        use chrono::TimeZone;
+        Interval {
+            activity,
+            start: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, start).unwrap(),
+            end: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, end).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_interval_sequential_direct_and_eventual() {
+        let traces = vec![vec![
+            interval("A", 1, 3),
+            interval("B", 3, 5),
+            interval("C", 6, 6),
+        ]];
+        let dep_ab = check_interval_temporal_dependency("A", "B", &traces, 1.0);
+        assert_eq!(
+            dep_ab,
+            Some(TemporalDependency::new("A", "B", DependencyType::Direct, Direction::Forward))
+        );
+        let dep_ca = check_interval_temporal_dependency("C", "A", &traces, 1.0);
+        assert_eq!(
+            dep_ca,
+            Some(TemporalDependency::new("C", "A", DependencyType::Eventual, Direction::Backward))
+        );
+    }
+
+    #[test]
+    fn test_interval_overlap_is_unordered() {
+        // A and B overlap in the first trace, so only half of the observations are ordered
+        let traces = vec![
+            vec![interval("A", 1, 4), interval("B", 2, 5)],
+            vec![interval("A", 1, 2), interval("B", 3, 5)],
+        ];
+        assert_eq!(check_interval_temporal_dependency("A", "B", &traces, 0.6), None);
+        assert_eq!(
+            check_interval_temporal_dependency("A", "B", &traces, 0.5),
+            Some(TemporalDependency::new("A", "B", DependencyType::Direct, Direction::Forward))
+        );
+    }
+
+    #[test]
+    fn test_interval_time_gaps() {
+        // Gaps from the end of the earlier to the start of the later occurrence, in both
+        // directions; overlapping and simultaneous occurrences have no gap
+        let traces = vec![
+            vec![interval("A", 1, 2), interval("B", 3, 3)],
+            vec![interval("A", 0, 0), interval("B", 10, 10)],
+            vec![interval("B", 1, 1), interval("A", 5, 6)],
+            vec![interval("A", 4, 4), interval("B", 4, 4)],
+        ];
+        assert_eq!(
+            interval_time_gaps("A", "B", &traces),
+            Some(TimeGaps {
+                min: Duration::seconds(1),
+                median: Duration::seconds(4),
+                max: Duration::seconds(10),
+                count: 3,
+            })
+        );
+        assert_eq!(interval_relation_counts("A", "B", &traces).unordered, 1);
+        assert_eq!(interval_time_gaps("A", "C", &traces), None);
+
+        let even = TimeGaps::from_gaps(vec![Duration::seconds(1), Duration::seconds(2)]).unwrap();
+        assert_eq!(even.median, Duration::milliseconds(1500));
+        assert_eq!(even.to_string(), "min 1s, median 1.5s, max 2s");
+    }
+
+    #[test]
+    fn test_trace_interval_observations_match_pairs() {
+        // Overlaps, touching intervals, simultaneous instants and repetitions
+        let trace = vec![
+            interval("A", 0, 2),
+            interval("B", 1, 3),
+            interval("C", 3, 3),
+            interval("C", 3, 3),
+            interval("A", 3, 5),
+            interval("D", 5, 5),
+            interval("B", 6, 6),
+            interval("A", 6, 8),
+            interval("D", 9, 9),
+        ];
+        let mut observations: BTreeMap<(&str, &str), (Vec<TimedRelation>, usize)> = BTreeMap::new();
+        trace_interval_observations(&trace, true, |from, to, relation| {
+            let (relations, unordered) = observations.entry((from, to)).or_default();
+            match relation {
+                Some(relation) => relations.push(relation),
+                None => *unordered += 1,
+            }
+        });
+
+        assert_eq!(observations.len(), 16);
+        for ((from, to), (mut relations, unordered)) in observations {
+            let (mut expected, expected_unordered) = check_trace_interval_pairs(from, to, &trace);
+            relations.sort();
+            expected.sort();
+            assert_eq!((relations, unordered), (expected, expected_unordered), "{} {}", from, to);
+        }
+    }
+
+    #[test]
+    fn test_interval_overlap_does_not_break_directness() {
+        // B runs concurrently to A and C, so it does not lie between them
+        let traces = vec![vec![
+            interval("A", 1, 3),
+            interval("B", 2, 6),
+            interval("C", 4, 5),
+        ]];
+        assert_eq!(
+            check_interval_temporal_dependency("A", "C", &traces, 1.0),
+            Some(TemporalDependency::new("A", "C", DependencyType::Direct, Direction::Forward))
+        );
+    }
+
+    #[test]
+    fn test_temporal_weighted_variants() {
+        // Four times A before B, once B before A
+        let variants = vec![(vec!["A", "B"], 3), (vec!["A", "X", "B"], 1), (vec!["B", "A"], 1)];
+        let traces: Vec<Vec<&str>> = variants
+            .iter()
+            .flat_map(|(trace, count)| std::iter::repeat_n(trace.clone(), *count))
+            .collect();
+        for threshold in [0.7, 0.8, 0.9] {
+            assert_eq!(
+                check_temporal_dependency("A", "B", &variants, threshold),
+                check_temporal_dependency("A", "B", &traces, threshold)
+            );
+        }
+        assert_eq!(
+            check_temporal_dependency("A", "B", &variants, 0.8),
+            Some(TemporalDependency::new("A", "B", DependencyType::Eventual, Direction::Forward))
+        );
+        assert_eq!(check_temporal_dependency("A", "B", &variants, 0.9), None);
+    }
+
+    #[test]
+    fn test_temporal_parallel() {
+        let traces = vec![
+            vec!["A", "B"],
+            vec!["B", "A"],
+            vec!["A", "B"],
+            vec!["A", "X", "B"],
+            vec!["B", "A"],
+        ];
+        // Forward 0.6 and backward 0.4: neither reaches 0.8, both reach 0.25
+        let parallel = |threshold, parallel| TemporalThresholds {
+            parallel: Some(parallel),
+            ..TemporalThresholds::uniform(threshold)
+        };
+        let dep = check_temporal_dependency_with_thresholds("A", "B", &traces, &parallel(0.8, 0.25));
+        assert_eq!(
+            dep,
+            Some(TemporalDependency::new("A", "B", DependencyType::Parallel, Direction::Both))
+        );
+        assert_eq!(dep.unwrap().to_string(), "A ∥ B");
+
+        let counts = temporal_relation_counts("A", "B", &traces);
+        assert_eq!(classify_dependencies("A", "B", &counts, &parallel(0.8, 0.5)), None);
+        // Parallel relations are opt-in
+        assert_eq!(check_temporal_dependency("A", "B", &traces, 0.8), None);
+
+        // A clear direction wins
+        assert_eq!(
+            check_temporal_dependency_with_thresholds("A", "B", &traces, &parallel(0.6, 0.25))
+                .map(|dep| dep.direction),
+            Some(Direction::Forward)
+        );
+        // A single reversed trace is noise, not parallelism
+        let traces = vec![vec!["A", "B"]; 9].into_iter().chain([vec!["B", "A"]]).collect::<Vec<_>>();
+        assert_eq!(check_temporal_dependency_with_thresholds("A", "B", &traces, &parallel(1.0, 0.25)), None);
+
+        // An order that is never observed is not parallel, even at a parallel threshold of 0
+        let traces = vec![vec!["A", "B"], vec!["A", "B"], vec!["A"]];
+        let thresholds = parallel(1.0, 0.0);
+        let mut counts = temporal_relation_counts("A", "B", &traces);
+        counts.unordered += 1;
+        assert_eq!(classify_dependencies("A", "B", &counts, &thresholds), None);
+    }
+
+    #[test]
+    fn test_temporal_parallel_without_dominant_direction() {
+        // The pairs of test_temporal_mixed_direction_no_dominant and
+        // test_temporal_independence_from_old_test, with parallel relations enabled
+        let thresholds = |threshold| TemporalThresholds {
+            parallel: Some(0.25),
+            ..TemporalThresholds::uniform(threshold)
+        };
+        let traces = vec![vec!["A", "B"], vec!["B", "A"]];
+        assert_eq!(
+            check_temporal_dependency_with_thresholds("A", "B", &traces, &thresholds(0.6)),
+            Some(TemporalDependency::new("A", "B", DependencyType::Parallel, Direction::Both))
+        );
+
+        let traces = vec![vec!["A", "B", "C", "C", "A"]];
+        let dep = check_temporal_dependency_with_thresholds("A", "C", &traces, &thresholds(0.9));
+        assert_eq!(dep.map(|dep| dep.dependency_type), Some(DependencyType::Parallel));
+    }
+
+    #[test]
+    fn test_temporal_type_aggregation() {
+        // A directly before B in 9 traces, eventually in 1
+        let traces = vec![vec!["A", "B"]; 9].into_iter().chain([vec!["A", "X", "B"]]).collect::<Vec<_>>();
+        let counts = temporal_relation_counts("A", "B", &traces);
+        let dependency_type = |type_aggregation| {
+            let thresholds = TemporalThresholds { type_aggregation, ..TemporalThresholds::uniform(1.0) };
+            classify_dependencies("A", "B", &counts, &thresholds).map(|dep| dep.dependency_type)
+        };
+        assert_eq!(dependency_type(TypeAggregation::AnyEventual), Some(DependencyType::Eventual));
+        assert_eq!(dependency_type(TypeAggregation::Majority), Some(DependencyType::Direct));
+        assert_eq!(dependency_type(TypeAggregation::DirectRatio(0.9)), Some(DependencyType::Direct));
+        assert_eq!(dependency_type(TypeAggregation::DirectRatio(0.95)), Some(DependencyType::Eventual));
+
+        for type_aggregation in [TypeAggregation::AnyEventual, TypeAggregation::Majority, TypeAggregation::DirectRatio(0.9)] {
+            assert_eq!(type_aggregation.to_string().parse(), Ok(type_aggregation));
+        }
+        assert!("direct-ratio=high".parse::<TypeAggregation>().is_err());
+    }
+
+    #[test]
+    fn test_temporal_direct_and_eventual_thresholds() {
+        // A directly before B and eventually before C in 3 of 4 traces
+        let traces = vec![
+            vec!["A", "B", "C"],
+            vec!["A", "B", "X", "C"],
+            vec!["A", "B", "C"],
+            vec!["B", "C", "A"],
+        ];
+        let strict_direct = TemporalThresholds { direct: 0.8, eventual: 0.7, parallel: Some(0.5), ..Default::default() };
+        assert_eq!(check_temporal_dependency_with_thresholds("A", "B", &traces, &strict_direct), None);
+        assert_eq!(
+            check_temporal_dependency_with_thresholds("A", "C", &traces, &strict_direct),
+            Some(TemporalDependency::new("A", "C", DependencyType::Eventual, Direction::Forward))
+        );
+
+        let strict_eventual = TemporalThresholds { direct: 0.7, eventual: 0.8, parallel: Some(0.5), ..Default::default() };
+        assert_eq!(
+            check_temporal_dependency_with_thresholds("A", "B", &traces, &strict_eventual),
+            Some(TemporalDependency::new("A", "B", DependencyType::Direct, Direction::Forward))
+        );
+        assert_eq!(check_temporal_dependency_with_thresholds("A", "C", &traces, &strict_eventual), None);
+    }
+}
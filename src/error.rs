@@ -0,0 +1,46 @@
+use crate::parser::ParseError;
+
+/// Errors of the classification pipeline, from reading the input to validating
+/// the thresholds.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Failed to read event log: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("The event log contains no events")]
+    EmptyLog,
+    #[error("{name} threshold must be between 0.0 and 1.0, got {value}")]
+    InvalidThreshold { name: &'static str, value: f64 },
+    #[error("Invalid analysis configuration: {0}")]
+    InvalidConfig(String),
+}
+
+/// Checks that a dependency threshold lies in `[0.0, 1.0]`.
+///
+/// `name` is used in the error message, e.g. `"Temporal"`.
+pub fn validate_threshold(name: &'static str, value: f64) -> Result<f64, Error> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(value)
+    } else {
+        Err(Error::InvalidThreshold { name, value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_threshold() {
+        assert_eq!(validate_threshold("Temporal", 0.8).unwrap(), 0.8);
+        assert!(matches!(
+            validate_threshold("Existential", 1.5),
+            Err(Error::InvalidThreshold {
+                name: "Existential",
+                ..
+            })
+        ));
+        assert!(validate_threshold("Temporal", f64::NAN).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, VecDeque};
+
+/// One execution of an activity, from its `start` to its `complete` transition.
+///
+/// Activities without a matching `start` transition are atomic: `start == end`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActivityInstance {
+    pub activity: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl ActivityInstance {
+    pub fn new(activity: String, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            activity,
+            start,
+            end,
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    /// Returns `true` if both instances were (at least partially) executed at the same time.
+    pub fn overlaps(&self, other: &ActivityInstance) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+}
+
+/// Returns `true` for the `complete` lifecycle transition. Like all transitions of the
+/// XES lifecycle extension, it is matched exactly (lower case).
+pub(crate) fn is_complete(transition: Option<&str>) -> bool {
+    transition == Some("complete")
+}
+
+/// Returns `true` for the `start` lifecycle transition, matched like [`is_complete`].
+fn is_start(transition: Option<&str>) -> bool {
+    transition == Some("start")
+}
+
+/// A timestamped event of a trace together with its `lifecycle:transition` value.
+pub(crate) struct LifecycleEvent<'a> {
+    pub activity: &'a str,
+    pub transition: Option<&'a str>,
+    pub date: DateTime<Utc>,
+}
+
+/// Pairs the `start` and `complete` transitions of a trace into activity instances.
+///
+/// Transitions are matched per activity in first-in-first-out order. A `complete`
+/// without an open `start`, an event without lifecycle information and a `start`
+/// that is never completed each become an atomic instance. All other transitions
+/// (`schedule`, `suspend`, ...) are ignored. Transitions are matched exactly, see
+/// [`is_complete`].
+/// The result is ordered by start time.
+pub(crate) fn pair_lifecycle_transitions(mut events: Vec<LifecycleEvent>) -> Vec<ActivityInstance> {
+    events.sort_by_key(|event| event.date);
+
+    let mut open_starts: HashMap<&str, VecDeque<usize>> = HashMap::new();
+    let mut instances: Vec<ActivityInstance> = Vec::new();
+
+    for event in events {
+        let instant = || ActivityInstance::new(event.activity.to_string(), event.date, event.date);
+        match event.transition {
+            transition if is_start(transition) => {
+                open_starts
+                    .entry(event.activity)
+                    .or_default()
+                    .push_back(instances.len());
+                instances.push(instant());
+            }
+            Some(_) if !is_complete(event.transition) => {}
+            _ => match open_starts
+                .get_mut(event.activity)
+                .and_then(|starts| starts.pop_front())
+            {
+                Some(index) => instances[index].end = event.date,
+                None => instances.push(instant()),
+            },
+        }
+    }
+
+    instances.sort_by_key(|instance| (instance.start, instance.end));
+    instances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event<'a>(
+        activity: &'a str,
+        transition: Option<&'a str>,
+        second: u32,
+    ) -> LifecycleEvent<'a> {
+        LifecycleEvent {
+            activity,
+            transition,
+            date: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, second).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_pair_lifecycle_transitions() {
+        let instances = pair_lifecycle_transitions(vec![
+            event("A", Some("start"), 1),
+            event("B", Some("start"), 2),
+            event("A", Some("complete"), 3),
+            event("B", Some("complete"), 5),
+            event("C", Some("complete"), 6),
+            event("C", Some("schedule"), 4),
+        ]);
+
+        let summary: Vec<(&str, i64)> = instances
+            .iter()
+            .map(|i| (i.activity.as_str(), i.duration().num_seconds()))
+            .collect();
+        assert_eq!(summary, vec![("A", 2), ("B", 3), ("C", 0)]);
+        assert!(instances[0].overlaps(&instances[1]));
+        assert!(!instances[1].overlaps(&instances[2]));
+    }
+
+    #[test]
+    fn test_lifecycle_transitions_are_matched_exactly() {
+        assert!(is_complete(Some("complete")));
+        assert!(!is_complete(Some("Complete")));
+
+        // Transitions in other cases are ignored like any other transition
+        let instances = pair_lifecycle_transitions(vec![
+            event("A", Some("Start"), 1),
+            event("A", Some("COMPLETE"), 2),
+            event("A", Some("start"), 3),
+            event("A", Some("Complete"), 4),
+            event("A", Some("complete"), 5),
+        ]);
+        let spans: Vec<(u32, u32)> = instances
+            .iter()
+            .map(|i| {
+                (
+                    i.start.timestamp() as u32 % 60,
+                    i.end.timestamp() as u32 % 60,
+                )
+            })
+            .collect();
+        assert_eq!(spans, vec![(3, 5)]);
+    }
+
+    #[test]
+    fn test_pair_lifecycle_transitions_fifo_per_activity() {
+        let instances = pair_lifecycle_transitions(vec![
+            event("A", Some("start"), 1),
+            event("A", Some("start"), 2),
+            event("A", Some("complete"), 3),
+            event("A", Some("complete"), 4),
+            event("A", Some("start"), 5),
+        ]);
+
+        let spans: Vec<(u32, u32)> = instances
+            .iter()
+            .map(|i| {
+                (
+                    i.start.timestamp() as u32 % 60,
+                    i.end.timestamp() as u32 % 60,
+                )
+            })
+            .collect();
+        assert_eq!(spans, vec![(1, 3), (2, 4), (5, 5)]);
+    }
+}
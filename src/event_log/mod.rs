@@ -0,0 +1,287 @@
+mod lifecycle;
+mod ties;
+mod variants;
+
+pub use lifecycle::ActivityInstance;
+pub use ties::{ParseReport, TiePolicy};
+pub use variants::{ActivityId, ActivityTable, VariantLog};
+
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::error::Error;
+
+pub(crate) use lifecycle::is_complete;
+use lifecycle::{pair_lifecycle_transitions, LifecycleEvent};
+
+/// Attribute values by key, converted to their string representation.
+pub type Attributes = BTreeMap<String, String>;
+
+/// An event log as read by the parsers, before it is reduced to activity sequences.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventLog {
+    pub cases: Vec<Case>,
+}
+
+/// A case (XES trace) with its events ordered by time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Case {
+    pub id: String,
+    pub attributes: Attributes,
+    pub events: Vec<Event>,
+    /// Whether an event the parser dropped (e.g. for lack of a timestamp) had the
+    /// lifecycle transition `complete`. The activity sequence then still consists of
+    /// the `complete` events only, as if all events of the case had been kept.
+    pub dropped_complete: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    /// Activity label, built from the configured activity keys or classifier.
+    pub activity: String,
+    pub timestamp: Option<DateTime<Utc>>,
+    /// Value of `lifecycle:transition`, if present.
+    pub lifecycle: Option<String>,
+    pub attributes: Attributes,
+}
+
+impl Event {
+    /// The resource (`org:resource`) that executed the event, if recorded.
+    pub fn resource(&self) -> Option<&str> {
+        self.attributes.get("org:resource").map(String::as_str)
+    }
+}
+
+impl Case {
+    /// The activity sequence of this case.
+    ///
+    /// If any event of the case has the lifecycle transition `complete`, only the
+    /// `complete` events are considered, so that each activity execution counts once.
+    pub fn activity_trace(&self) -> Vec<&str> {
+        self.trace_events()
+            .map(|event| event.activity.as_str())
+            .collect()
+    }
+
+    /// The events of [`Case::activity_trace`] with their timestamps.
+    pub(crate) fn timed_activity_trace(&self) -> Vec<(&str, Option<DateTime<Utc>>)> {
+        self.trace_events()
+            .map(|event| (event.activity.as_str(), event.timestamp))
+            .collect()
+    }
+
+    /// The events of [`Case::activity_trace`] as instants at their timestamp, so that
+    /// events with identical timestamps are unordered. Events without timestamp are
+    /// ignored.
+    pub fn activity_instants(&self) -> Vec<ActivityInstance> {
+        self.trace_events()
+            .filter_map(|event| {
+                let timestamp = event.timestamp?;
+                Some(ActivityInstance::new(
+                    event.activity.clone(),
+                    timestamp,
+                    timestamp,
+                ))
+            })
+            .collect()
+    }
+
+    /// The events forming the activity sequence: only the `complete` events if there
+    /// are any.
+    fn trace_events(&self) -> impl Iterator<Item = &Event> {
+        let has_complete = self.dropped_complete
+            || self
+                .events
+                .iter()
+                .any(|event| is_complete(event.lifecycle.as_deref()));
+
+        self.events
+            .iter()
+            .filter(move |event| !has_complete || is_complete(event.lifecycle.as_deref()))
+    }
+
+    /// The activity instances of this case, pairing `start` and `complete` transitions
+    /// (see [`ActivityInstance`]). Events without timestamp are ignored.
+    pub fn activity_instances(&self) -> Vec<ActivityInstance> {
+        pair_lifecycle_transitions(
+            self.events
+                .iter()
+                .filter_map(|event| {
+                    Some(LifecycleEvent {
+                        activity: &event.activity,
+                        transition: event.lifecycle.as_deref(),
+                        date: event.timestamp?,
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+impl EventLog {
+    /// Fails with [`Error::EmptyLog`] if no case contains any event.
+    pub(crate) fn non_empty(self) -> Result<Self, Error> {
+        if self.cases.iter().all(|case| case.events.is_empty()) {
+            Err(Error::EmptyLog)
+        } else {
+            Ok(self)
+        }
+    }
+
+    pub fn case_ids(&self) -> Vec<&str> {
+        self.cases.iter().map(|case| case.id.as_str()).collect()
+    }
+
+    /// The activity instances of every case, in case order.
+    pub fn activity_instances(&self) -> Vec<Vec<ActivityInstance>> {
+        self.cases.iter().map(Case::activity_instances).collect()
+    }
+
+    /// The activity instants of every case (see [`Case::activity_instants`]), in case
+    /// order.
+    pub fn activity_instants(&self) -> Vec<Vec<ActivityInstance>> {
+        self.cases.iter().map(Case::activity_instants).collect()
+    }
+
+    /// Ids of the cases in which all of the given activities occur, e.g. the cases
+    /// contributing to the existential dependency between two activities.
+    pub fn cases_containing(&self, activities: &[&str]) -> Vec<&str> {
+        self.cases
+            .iter()
+            .filter(|case| {
+                let trace = case.activity_trace();
+                activities.iter().all(|activity| trace.contains(activity))
+            })
+            .map(|case| case.id.as_str())
+            .collect()
+    }
+}
+
+/// Read-only view of a log as activity sequences, one per case.
+///
+/// This is what the matrix generation consumes, so it can be fed an [`EventLog`]
+/// as well as plain traces (`Vec<Vec<String>>`).
+pub trait TraceView {
+    fn activity_traces(&self) -> Vec<Vec<&str>>;
+
+    /// The distinct activity sequences with their number of traces, in order of
+    /// first appearance.
+    fn trace_variants(&self) -> Vec<(Vec<&str>, usize)> {
+        let mut variants: Vec<(Vec<&str>, usize)> = Vec::new();
+        let mut positions: HashMap<Vec<&str>, usize> = HashMap::new();
+        for trace in self.activity_traces() {
+            match positions.get(&trace) {
+                Some(&position) => variants[position].1 += 1,
+                None => {
+                    positions.insert(trace.clone(), variants.len());
+                    variants.push((trace, 1));
+                }
+            }
+        }
+        variants
+    }
+}
+
+impl TraceView for EventLog {
+    fn activity_traces(&self) -> Vec<Vec<&str>> {
+        self.cases.iter().map(Case::activity_trace).collect()
+    }
+}
+
+impl<S: AsRef<str>> TraceView for [Vec<S>] {
+    fn activity_traces(&self) -> Vec<Vec<&str>> {
+        self.iter()
+            .map(|trace| trace.iter().map(|activity| activity.as_ref()).collect())
+            .collect()
+    }
+}
+
+impl<S: AsRef<str>> TraceView for Vec<Vec<S>> {
+    fn activity_traces(&self) -> Vec<Vec<&str>> {
+        self.as_slice().activity_traces()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(activity: &str, lifecycle: Option<&str>) -> Event {
+        Event {
+            activity: activity.to_string(),
+            timestamp: None,
+            lifecycle: lifecycle.map(str::to_string),
+            attributes: Attributes::new(),
+        }
+    }
+
+    fn case(id: &str, events: Vec<Event>) -> Case {
+        Case {
+            id: id.to_string(),
+            attributes: Attributes::new(),
+            events,
+            dropped_complete: false,
+        }
+    }
+
+    #[test]
+    fn test_activity_trace_keeps_complete_events_only_if_present() {
+        let with_lifecycle = case(
+            "1",
+            vec![
+                event("A", Some("start")),
+                event("A", Some("complete")),
+                event("B", Some("complete")),
+            ],
+        );
+        let without_lifecycle = case("2", vec![event("A", None), event("C", None)]);
+        let dropped_complete = Case {
+            dropped_complete: true,
+            ..case("3", vec![event("A", Some("start")), event("C", None)])
+        };
+
+        assert_eq!(with_lifecycle.activity_trace(), vec!["A", "B"]);
+        assert_eq!(without_lifecycle.activity_trace(), vec!["A", "C"]);
+        assert!(dropped_complete.activity_trace().is_empty());
+    }
+
+    #[test]
+    fn test_activity_instants() {
+        use chrono::TimeZone;
+        let at = |activity, lifecycle, second: Option<u32>| Event {
+            timestamp: second.map(|second| Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, second).unwrap()),
+            ..event(activity, lifecycle)
+        };
+        let case = case(
+            "1",
+            vec![
+                at("A", Some("start"), Some(0)),
+                at("A", Some("complete"), Some(1)),
+                at("B", Some("complete"), Some(1)),
+                at("C", Some("complete"), None),
+            ],
+        );
+
+        let instants = case.activity_instants();
+        assert_eq!(instants.len(), 2);
+        assert_eq!(instants[0].activity, "A");
+        assert_eq!(instants[1].activity, "B");
+        assert_eq!(instants[0].start, instants[1].end);
+        assert!(instants.iter().all(|instant| instant.start == instant.end));
+    }
+
+    #[test]
+    fn test_event_log_views() {
+        let log = EventLog {
+            cases: vec![
+                case("1", vec![event("A", None), event("B", None)]),
+                case("2", vec![event("A", None), event("C", None)]),
+            ],
+        };
+
+        assert_eq!(log.case_ids(), vec!["1", "2"]);
+        assert_eq!(log.activity_traces(), vec![vec!["A", "B"], vec!["A", "C"]]);
+        assert_eq!(log.cases_containing(&["A", "C"]), vec!["2"]);
+        assert_eq!(log.cases_containing(&["A"]), vec!["1", "2"]);
+    }
+}
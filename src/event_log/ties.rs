@@ -0,0 +1,222 @@
+use super::{Case, Event, EventLog};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// How events of a case with identical timestamps are ordered.
+///
+/// Logs with coarse timestamps (e.g. day granularity) contain many such ties, and their
+/// order decides between direct and eventual relations.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TiePolicy {
+    /// Keep the order of the events in the log file.
+    #[default]
+    DocumentOrder,
+    /// Treat the events as concurrent: they are not ordered at all, and temporal
+    /// dependencies are computed on the timestamps (recording the time gaps between
+    /// related activities). Events without timestamp cannot be kept with this policy.
+    Concurrent,
+    /// Order the events by the value of this event attribute, numbers before text and
+    /// events without the attribute last; equal values keep document order.
+    Attribute(String),
+}
+
+impl std::fmt::Display for TiePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TiePolicy::DocumentOrder => write!(f, "document-order"),
+            TiePolicy::Concurrent => write!(f, "concurrent"),
+            TiePolicy::Attribute(key) => write!(f, "attribute={}", key),
+        }
+    }
+}
+
+impl std::str::FromStr for TiePolicy {
+    type Err = String;
+
+    /// Parses "document-order", "concurrent" or "attribute=<key>".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            None if s == "document-order" => Ok(TiePolicy::DocumentOrder),
+            None if s == "concurrent" => Ok(TiePolicy::Concurrent),
+            Some(("attribute", key)) if !key.is_empty() => {
+                Ok(TiePolicy::Attribute(key.to_string()))
+            }
+            _ => Err(format!(
+                "unknown tie policy '{}', expected document-order, concurrent or attribute=<key>",
+                s
+            )),
+        }
+    }
+}
+
+/// Timestamp ties found in a parsed log, among the events forming the activity
+/// sequences (see [`Case::activity_trace`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseReport {
+    pub cases: usize,
+    pub events: usize,
+    /// Events sharing their timestamp with another event of the same case.
+    pub tied_events: usize,
+    /// Distinct timestamps shared by several events of a case, summed over all cases.
+    pub tie_groups: usize,
+    /// Cases with at least one tie.
+    pub cases_with_ties: usize,
+}
+
+impl std::fmt::Display for ParseReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} events in {} cases, {} events with equal timestamps in {} groups ({} cases)",
+            self.events, self.cases, self.tied_events, self.tie_groups, self.cases_with_ties
+        )
+    }
+}
+
+impl EventLog {
+    /// Counts the timestamp ties of every case.
+    pub fn parse_report(&self) -> ParseReport {
+        let mut report = ParseReport {
+            cases: self.cases.len(),
+            ..ParseReport::default()
+        };
+        for case in &self.cases {
+            let mut events_at: HashMap<_, usize> = HashMap::new();
+            for event in case.trace_events() {
+                report.events += 1;
+                if let Some(timestamp) = event.timestamp {
+                    *events_at.entry(timestamp).or_default() += 1;
+                }
+            }
+            let ties: Vec<usize> = events_at.into_values().filter(|&count| count > 1).collect();
+            report.tied_events += ties.iter().sum::<usize>();
+            report.tie_groups += ties.len();
+            report.cases_with_ties += usize::from(!ties.is_empty());
+        }
+        report
+    }
+
+    /// Reorders consecutive events with identical timestamps according to `policy`.
+    ///
+    /// Only [`TiePolicy::Attribute`] changes the order: the parsers already keep
+    /// document order, and concurrent events are handled by the temporal dependencies.
+    pub fn order_ties(&mut self, policy: &TiePolicy) {
+        if let TiePolicy::Attribute(key) = policy {
+            for case in &mut self.cases {
+                case.order_ties_by_attribute(key);
+            }
+        }
+    }
+}
+
+impl Case {
+    fn order_ties_by_attribute(&mut self, key: &str) {
+        for tie in self
+            .events
+            .chunk_by_mut(|a, b| a.timestamp.is_some() && a.timestamp == b.timestamp)
+        {
+            tie.sort_by(|a, b| compare_attribute(a, b, key));
+        }
+    }
+}
+
+/// Total order of the `key` values of two events: numbers (by value), then text, then
+/// events without the attribute.
+fn compare_attribute(a: &Event, b: &Event, key: &str) -> Ordering {
+    let value = |event: &Event| {
+        event
+            .attributes
+            .get(key)
+            .map(|value| match value.parse::<f64>() {
+                Ok(number) => Ok(number),
+                Err(_) => Err(value.clone()),
+            })
+    };
+    match (value(a), value(b)) {
+        (Some(Ok(a)), Some(Ok(b))) => a.total_cmp(&b),
+        (Some(Err(a)), Some(Err(b))) => a.cmp(&b),
+        (Some(Ok(_)), Some(Err(_))) | (Some(_), None) => Ordering::Less,
+        (Some(Err(_)), Some(Ok(_))) | (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_log::Attributes;
+    use chrono::{TimeZone, Utc};
+
+    fn event(activity: &str, second: Option<u32>, priority: Option<&str>) -> Event {
+        Event {
+            activity: activity.to_string(),
+            timestamp: second.map(|second| Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, second).unwrap()),
+            lifecycle: None,
+            attributes: priority
+                .map(|priority| Attributes::from([("priority".to_string(), priority.to_string())]))
+                .unwrap_or_default(),
+        }
+    }
+
+    #[test]
+    fn test_parse_report_and_attribute_order() {
+        let mut log = EventLog {
+            cases: vec![
+                Case {
+                    id: "1".to_string(),
+                    events: vec![
+                        event("A", Some(0), None),
+                        event("B", Some(1), Some("x")),
+                        event("C", Some(1), Some("10")),
+                        event("D", Some(1), Some("9")),
+                        event("E", Some(2), None),
+                        event("F", Some(2), Some("1")),
+                    ],
+                    ..Case::default()
+                },
+                Case {
+                    id: "2".to_string(),
+                    events: vec![event("A", Some(0), None), event("B", Some(1), None)],
+                    ..Case::default()
+                },
+            ],
+        };
+
+        assert_eq!(
+            log.parse_report(),
+            ParseReport {
+                cases: 2,
+                events: 8,
+                tied_events: 5,
+                tie_groups: 2,
+                cases_with_ties: 1,
+            }
+        );
+
+        log.order_ties(&TiePolicy::DocumentOrder);
+        assert_eq!(
+            log.cases[0].activity_trace(),
+            vec!["A", "B", "C", "D", "E", "F"]
+        );
+        log.order_ties(&TiePolicy::Attribute("priority".to_string()));
+        assert_eq!(
+            log.cases[0].activity_trace(),
+            vec!["A", "D", "C", "B", "F", "E"]
+        );
+    }
+
+    #[test]
+    fn test_tie_policy_from_str() {
+        for policy in [
+            TiePolicy::DocumentOrder,
+            TiePolicy::Concurrent,
+            TiePolicy::Attribute("org:resource".to_string()),
+        ] {
+            assert_eq!(policy.to_string().parse(), Ok(policy));
+        }
+        assert!("attribute=".parse::<TiePolicy>().is_err());
+        assert!("random".parse::<TiePolicy>().is_err());
+    }
+}
@@ -0,0 +1,198 @@
+use super::TraceView;
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Index of an activity in an [`ActivityTable`].
+pub type ActivityId = u32;
+
+/// Interned activity names: every distinct name is stored once and referred to by
+/// its [`ActivityId`], assigned in order of first appearance.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActivityTable {
+    names: Vec<String>,
+    ids: HashMap<String, ActivityId>,
+}
+
+impl ActivityTable {
+    /// Returns the id of `name`, adding it to the table if it is new.
+    pub fn intern(&mut self, name: &str) -> ActivityId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len() as ActivityId;
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Option<ActivityId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: ActivityId) -> &str {
+        &self.names[id as usize]
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// A log reduced to its trace variants (distinct activity sequences) and how often
+/// each of them occurs.
+///
+/// This is all the dependency computation needs, so its size depends on the number
+/// of variants and activities, not on the number of traces or events.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VariantLog {
+    activities: ActivityTable,
+    variants: Vec<(Vec<ActivityId>, usize)>,
+    /// Positions in `variants` by the hash of the variant, so that variants are not
+    /// stored twice. A variant whose hash is taken by another one goes to the next free
+    /// key (`hash + 1`, ...).
+    positions: HashMap<u64, usize>,
+}
+
+impl VariantLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_traces<T: TraceView + ?Sized>(traces: &T) -> Self {
+        let mut log = Self::new();
+        for trace in traces.activity_traces() {
+            log.add_trace(trace);
+        }
+        log
+    }
+
+    /// Adds one trace, either as a new variant or by counting an existing one.
+    pub fn add_trace<'a>(&mut self, trace: impl IntoIterator<Item = &'a str>) {
+        let variant: Vec<ActivityId> = trace
+            .into_iter()
+            .map(|activity| self.activities.intern(activity))
+            .collect();
+        let mut hasher = DefaultHasher::new();
+        variant.hash(&mut hasher);
+        self.add_variant(variant, hasher.finish());
+    }
+
+    fn add_variant(&mut self, variant: Vec<ActivityId>, hash: u64) {
+        let mut key = hash;
+        loop {
+            match self.positions.entry(key) {
+                Entry::Occupied(entry) if self.variants[*entry.get()].0 == variant => {
+                    self.variants[*entry.get()].1 += 1;
+                    return;
+                }
+                Entry::Occupied(_) => key = key.wrapping_add(1),
+                Entry::Vacant(entry) => {
+                    entry.insert(self.variants.len());
+                    self.variants.push((variant, 1));
+                    return;
+                }
+            }
+        }
+    }
+
+    /// The activities of the variants, indexed by their [`ActivityId`].
+    pub fn activities(&self) -> &ActivityTable {
+        &self.activities
+    }
+
+    /// The variants with their number of traces, in order of first appearance.
+    pub fn variants(&self) -> impl Iterator<Item = (&[ActivityId], usize)> {
+        self.variants
+            .iter()
+            .map(|(variant, count)| (variant.as_slice(), *count))
+    }
+
+    pub fn variant_count(&self) -> usize {
+        self.variants.len()
+    }
+
+    pub fn trace_count(&self) -> usize {
+        self.variants.iter().map(|(_, count)| count).sum()
+    }
+}
+
+/// Expands the variants back into one activity sequence per trace.
+impl TraceView for VariantLog {
+    fn activity_traces(&self) -> Vec<Vec<&str>> {
+        self.variants
+            .iter()
+            .flat_map(|(variant, count)| {
+                let trace: Vec<&str> = variant.iter().map(|&id| self.activities.name(id)).collect();
+                std::iter::repeat_n(trace, *count)
+            })
+            .collect()
+    }
+
+    fn trace_variants(&self) -> Vec<(Vec<&str>, usize)> {
+        self.variants()
+            .map(|(variant, count)| {
+                let trace = variant.iter().map(|&id| self.activities.name(id)).collect();
+                (trace, count)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variant_log_counts_and_interns() {
+        let traces = vec![
+            vec!["A", "B", "C"],
+            vec!["A", "B", "C"],
+            vec!["B", "C", "D"],
+            vec!["A", "B", "C"],
+        ];
+        let log = VariantLog::from_traces(&traces);
+
+        assert_eq!(log.variant_count(), 2);
+        assert_eq!(log.trace_count(), 4);
+        assert_eq!(log.activities().names(), &["A", "B", "C", "D"]);
+        let counts: Vec<usize> = log.variants().map(|(_, count)| count).collect();
+        assert_eq!(counts, vec![3, 1]);
+
+        // Traces come back grouped by variant
+        assert_eq!(
+            log.activity_traces(),
+            vec![
+                vec!["A", "B", "C"],
+                vec!["A", "B", "C"],
+                vec!["A", "B", "C"],
+                vec!["B", "C", "D"],
+            ]
+        );
+        assert_eq!(log.trace_variants(), traces.trace_variants());
+    }
+
+    #[test]
+    fn test_variant_log_separates_colliding_hashes() {
+        let mut log = VariantLog::new();
+        log.add_variant(vec![0, 1], 7);
+        log.add_variant(vec![1, 0], 7);
+        log.add_variant(vec![1, 0], 7);
+        log.add_variant(vec![2], 8);
+        log.add_variant(vec![0, 1], 7);
+
+        let variants: Vec<(&[ActivityId], usize)> = log.variants().collect();
+        assert_eq!(
+            variants,
+            vec![(&[0, 1][..], 2), (&[1, 0][..], 2), (&[2][..], 1)]
+        );
+    }
+}
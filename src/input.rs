@@ -0,0 +1,262 @@
+use flate2::bufread::MultiGzDecoder;
+use quick_xml::events::Event;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// Event log formats, as told apart by the file name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LogFormat {
+    Xes,
+    Csv,
+    /// ProM MXML log.
+    Mxml,
+    /// OCEL 2.0 object-centric log (JSON or XML).
+    Ocel,
+}
+
+impl LogFormat {
+    /// Detects the format from the file extension, ignoring a trailing `.gz`.
+    /// Files with an unknown extension are assumed to be XES, `.xml` files to be OCEL
+    /// (see [`LogFormat::detect`] to tell them from MXML).
+    pub fn from_file_name(file_name: &str) -> Self {
+        match extension(file_name).as_str() {
+            "csv" => LogFormat::Csv,
+            "mxml" => LogFormat::Mxml,
+            "json" | "jsonocel" | "xml" | "xmlocel" => LogFormat::Ocel,
+            _ => LogFormat::Xes,
+        }
+    }
+
+    /// Detects the format like [`LogFormat::from_file_name`], but reads the root element
+    /// of `.xml` files: `<WorkflowLog>` is an MXML log, anything else an OCEL 2.0 log.
+    ///
+    /// Returns the format together with the source to read the log from.
+    pub fn detect<'a>(file_name: &str, source: InputSource<'a>) -> io::Result<(Self, InputSource<'a>)> {
+        if extension(file_name) != "xml" {
+            return Ok((Self::from_file_name(file_name), source));
+        }
+        let mut reader = source.into_buf_read()?;
+        let format = match xml_root_element(reader.fill_buf()?).as_deref() {
+            Some(b"WorkflowLog") => LogFormat::Mxml,
+            _ => LogFormat::Ocel,
+        };
+        Ok((format, InputSource::Reader(Box::new(reader))))
+    }
+}
+
+/// The lower-case file extension, ignoring a trailing `.gz`.
+fn extension(file_name: &str) -> String {
+    let file_name = file_name.to_lowercase();
+    let file_name = file_name.trim_end_matches(".gz");
+    file_name
+        .rsplit_once('.')
+        .map_or("", |(_, extension)| extension)
+        .to_string()
+}
+
+/// Name of the first element in the beginning of an XML document, if it is there.
+fn xml_root_element(head: &[u8]) -> Option<Vec<u8>> {
+    let mut reader = quick_xml::Reader::from_reader(head);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf).ok()? {
+            Event::Start(element) | Event::Empty(element) => {
+                return Some(element.name().as_ref().to_vec())
+            }
+            Event::Eof => return None,
+            _ => buf.clear(),
+        }
+    }
+}
+
+/// Magic bytes at the start of every gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Where an event log is read from.
+///
+/// Gzip-compressed content (e.g. `.xes.gz` files) is detected by its magic bytes
+/// and decompressed transparently, whatever the source.
+pub enum InputSource<'a> {
+    /// A file on disk.
+    Path(PathBuf),
+    /// The raw file content, e.g. an upload in the web UI.
+    Bytes(&'a [u8]),
+    /// Any other reader (network stream, decompressor, ...).
+    Reader(Box<dyn Read + 'a>),
+    /// The standard input of the process.
+    Stdin,
+}
+
+impl<'a> InputSource<'a> {
+    pub fn path(path: impl Into<PathBuf>) -> Self {
+        InputSource::Path(path.into())
+    }
+
+    /// Opens the source for buffered reading, decompressing gzip content.
+    /// Fails if a file cannot be opened.
+    pub(crate) fn into_buf_read(self) -> io::Result<Box<dyn BufRead + 'a>> {
+        let mut reader: Box<dyn BufRead + 'a> = match self {
+            InputSource::Path(path) => Box::new(BufReader::new(File::open(path)?)),
+            InputSource::Bytes(bytes) => Box::new(bytes),
+            InputSource::Reader(reader) => Box::new(BufReader::new(reader)),
+            InputSource::Stdin => Box::new(io::stdin().lock()),
+        };
+        if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+            reader = Box::new(BufReader::new(MultiGzDecoder::new(reader)));
+        }
+        Ok(reader)
+    }
+
+    /// Reads the whole source into memory.
+    pub(crate) fn read_to_end(self) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.into_buf_read()?.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+/// SHA-256 digest of the (decompressed) content of an [`InputSource`], computed while
+/// the log is parsed from [`InputDigest::source`].
+#[derive(Default)]
+pub(crate) struct InputDigest {
+    hasher: Rc<RefCell<Sha256>>,
+}
+
+impl InputDigest {
+    /// A source reading `source` and adding everything read to the digest. The rest of
+    /// the content is added when the source is dropped, so that content a parser does
+    /// not read (e.g. after the end of an XML document) is covered as well.
+    pub(crate) fn source<'a>(&self, source: InputSource<'a>) -> io::Result<InputSource<'a>> {
+        Ok(InputSource::Reader(Box::new(DigestReader {
+            inner: source.into_buf_read()?,
+            hasher: Rc::clone(&self.hasher),
+        })))
+    }
+
+    /// The digest as lower-case hex, once the source is dropped.
+    pub(crate) fn finish(self) -> String {
+        self.hasher
+            .take()
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+struct DigestReader<'a> {
+    inner: Box<dyn BufRead + 'a>,
+    hasher: Rc<RefCell<Sha256>>,
+}
+
+impl Read for DigestReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = self.inner.read(buf)?;
+        self.hasher.borrow_mut().update(&buf[..count]);
+        Ok(count)
+    }
+}
+
+impl Drop for DigestReader<'_> {
+    fn drop(&mut self) {
+        // The digest of a source that cannot be read to the end is never finished
+        let _ = io::copy(&mut self.inner, &mut *self.hasher.borrow_mut());
+    }
+}
+
+impl<'a> From<&'a [u8]> for InputSource<'a> {
+    fn from(bytes: &'a [u8]) -> Self {
+        InputSource::Bytes(bytes)
+    }
+}
+
+impl std::fmt::Debug for InputSource<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::Path(path) => f.debug_tuple("Path").field(path).finish(),
+            InputSource::Bytes(bytes) => write!(f, "Bytes({} bytes)", bytes.len()),
+            InputSource::Reader(_) => f.write_str("Reader"),
+            InputSource::Stdin => f.write_str("Stdin"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    #[test]
+    fn test_log_format_from_file_name() {
+        assert_eq!(LogFormat::from_file_name("log.xes"), LogFormat::Xes);
+        assert_eq!(LogFormat::from_file_name("BPI_2012.XES.gz"), LogFormat::Xes);
+        assert_eq!(LogFormat::from_file_name("log.csv.gz"), LogFormat::Csv);
+        assert_eq!(LogFormat::from_file_name("legacy.MXML"), LogFormat::Mxml);
+        assert_eq!(
+            LogFormat::from_file_name("order-management.json"),
+            LogFormat::Ocel
+        );
+        assert_eq!(LogFormat::from_file_name("-"), LogFormat::Xes);
+    }
+
+    #[test]
+    fn test_log_format_detect_xml_root_element() {
+        let detect = |file_name, content: &str| {
+            LogFormat::detect(file_name, InputSource::Bytes(content.as_bytes()))
+                .map(|(format, source)| (format, source.read_to_end().unwrap()))
+                .unwrap()
+        };
+        let mxml = r#"<?xml version="1.0"?><!-- ProM --><WorkflowLog><Process/></WorkflowLog>"#;
+        assert_eq!(
+            detect("legacy.xml", mxml),
+            (LogFormat::Mxml, mxml.as_bytes().to_vec())
+        );
+        assert_eq!(detect("orders.xml", "<log><events/></log>").0, LogFormat::Ocel);
+        // Other extensions are not read
+        assert_eq!(detect("legacy.mxml", "").0, LogFormat::Mxml);
+    }
+
+    #[test]
+    fn test_input_digest_covers_the_whole_decompressed_content() {
+        let content = b"<log></log>\n<!-- trailing -->";
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(content).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let digest = InputDigest::default();
+        let Ok(InputSource::Reader(mut reader)) = digest.source(InputSource::Bytes(&compressed)) else {
+            panic!("the digest is computed by a reader");
+        };
+        // Only the beginning is read, the rest is added on drop
+        reader.read_exact(&mut [0; 5]).unwrap();
+        drop(reader);
+        assert_eq!(
+            digest.finish(),
+            Sha256::digest(content)
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>()
+        );
+    }
+
+    #[test]
+    fn test_gzip_content_is_decompressed() {
+        let content = b"<log></log>";
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(content).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(
+            InputSource::Bytes(&compressed).read_to_end().unwrap(),
+            content
+        );
+        assert_eq!(InputSource::Bytes(content).read_to_end().unwrap(), content);
+    }
+}
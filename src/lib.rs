@@ -0,0 +1,72 @@
+//! Classification of event logs by the structure of their activity dependencies.
+//!
+//! The typical pipeline is:
+//!
+//! 1. [`parse_event_log`] reads an XES log (or [`parse_csv_event_log`] a CSV log)
+//!    into a typed [`EventLog`] of cases and events; [`parse_into_traces`] returns
+//!    just the activity traces,
+//! 2. [`generate_dependency_matrix`] discovers the temporal and existential
+//!    [`Dependency`] between every pair of activities,
+//! 3. [`classify_matrix`] applies the classification rules to the matrix and
+//!    returns a [`ClassificationOutput`].
+//!
+//! [`analyze`] runs all three steps on an [`AnalysisConfig`] and records every result
+//! with a [`Manifest`] of the configuration, so that the run can be reproduced.
+//!
+//! ```no_run
+//! use matrix_classifier::{
+//!     classify_matrix, generate_dependency_matrix, parse_event_log, InputSource,
+//! };
+//!
+//! let source = InputSource::path("event-logs/Log01_structured.xes");
+//! let event_log = parse_event_log(source, &Default::default()).unwrap();
+//! let matrix = generate_dependency_matrix(&event_log, 1.0, 1.0);
+//! let output = classify_matrix(&matrix);
+//! println!("{}", output.classification);
+//! ```
+
+pub mod analysis;
+pub mod classification;
+pub mod dependency_matrix;
+pub mod dependency_types;
+pub mod error;
+pub mod event_log;
+pub mod input;
+pub mod matrix_generation;
+mod occurrence_index;
+mod parallel;
+pub mod parser;
+
+pub use analysis::{analyze, Analysis, AnalysisConfig, Manifest};
+pub use classification::{
+    classify_matrix, classify_matrix_with_options, Activity, CalculatedPercentages,
+    ClassifiableMatrix, Classification, ClassificationOptions, ClassificationOutput, InputMatrix,
+    RULE_SET_VERSION,
+};
+pub use dependency_matrix::{DependencyCell, DependencyMatrix};
+pub use dependency_types::dependency::{Dependency, DependencyStatistics};
+pub use dependency_types::{
+    existential::ExistentialThresholds,
+    temporal::{TemporalThresholds, TypeAggregation},
+};
+pub use error::{validate_threshold, Error};
+pub use event_log::{
+    ActivityId, ActivityInstance, ActivityTable, Case, Event, EventLog, ParseReport, TiePolicy,
+    TraceView, VariantLog,
+};
+pub use input::{InputSource, LogFormat};
+pub use matrix_generation::{
+    generate_dependency_matrix, generate_indexed_dependency_matrix,
+    generate_indexed_dependency_matrix_with_thresholds,
+    generate_indexed_event_log_dependency_matrix_with_thresholds,
+    generate_indexed_interval_dependency_matrix,
+    generate_indexed_interval_dependency_matrix_with_thresholds, generate_interval_dependency_matrix,
+    MatrixOptions, Thresholds,
+};
+pub use parser::{
+    flatten_ocel, list_classifiers, ocel_object_types, parse_csv_event_log, parse_csv_into_traces,
+    parse_event_log, parse_into_activity_instances, parse_into_traces,
+    parse_into_traces_with_options, parse_mxml_event_log, parse_mxml_into_traces, parse_ocel,
+    parse_ocel_event_logs, parse_xes_variants, CsvImportOptions, EventLogClassifier, ParseError,
+    ParseOptions,
+};
@@ -1,350 +1,388 @@
-mod classification;
-mod dependency_types;
-mod matrix_generation;
-mod parser;
-
-use classification::{
-    classify_matrix, ClassificationOutput, CalculatedPercentages,
-};
-use matrix_generation::generate_dependency_matrix;
-use parser::parse_into_traces;
-
-use std::rc::Rc;
-use wasm_bindgen::prelude::*;
-use wasm_bindgen_futures::spawn_local;
-use web_sys::{HtmlInputElement, MouseEvent, ProgressEvent, FileReader, Event, InputEvent};
-use yew::prelude::*;
 use clap::Parser;
+use matrix_classifier::{
+    analyze, list_classifiers, validate_threshold, AnalysisConfig, CalculatedPercentages,
+    ClassificationOptions, CsvImportOptions, Error, ExistentialThresholds, InputSource, Manifest,
+    MatrixOptions, ParseOptions, TemporalThresholds, Thresholds, TiePolicy, TypeAggregation,
+    RULE_SET_VERSION,
+};
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
+    /// Event log to classify: XES, CSV (.csv), MXML (.mxml) or OCEL 2.0 (.json, .jsonocel, .xml,
+    /// .xmlocel),
+    /// optionally gzip-compressed (.xes.gz, .csv.gz, ...); "-" reads XES from stdin
     #[clap(short, long, value_parser)]
-    file_path: Option<String>,
+    file_path: String,
+
+    /// Read the analysis configuration (thresholds, parser and classification options)
+    /// from a JSON file, e.g. a manifest written by --manifest to repeat that run; it
+    /// cannot be combined with analysis options on the command line
+    #[clap(long)]
+    config: Option<String>,
+
+    /// Write the manifest of every result (input, configuration and classification) as
+    /// a JSON list to this file, "-" prints it
+    #[clap(long)]
+    manifest: Option<String>,
 
     #[clap(long)]
     print_ratios: bool,
 
-    #[clap(long, default_value_t = 1.0)]
-    temporal_threshold: f64,
+    /// Write the dependency matrix with the statistics and time gaps of every dependency
+    /// as CSV to this file; for several object types of an OCEL log, the object type is
+    /// inserted before the file extension
+    #[clap(long)]
+    export_matrix: Option<String>,
 
-    #[clap(long, default_value_t = 1.0)]
-    existential_threshold: f64,
-}
+    /// Print every dependency of the matrix with the number of relevant traces and the
+    /// observed ratio of each candidate relation
+    #[clap(long)]
+    print_dependencies: bool,
 
-#[derive(Debug, thiserror::Error, Clone, PartialEq)]
-enum AppError {
-    #[error("File reading error: {0}")]
-    FileReadError(String),
-    #[error("XES parsing error: {0}")]
-    XesParseError(String),
-    #[error("Classification error: {0}")]
-    ClassificationError(String),
-}
+    #[clap(long, default_value_t = 1.0, conflicts_with = "config")]
+    temporal_threshold: f64,
 
-enum AppMessage {
-    FileSelected(Option<String>),
-    FileLoaded(Result<String, String>),
-    ExistentialThresholdChanged(String),
-    TemporalThresholdChanged(String),
-    ProcessLog,
-    SetClassificationResult(Result<ClassificationOutput, AppError>),
-}
+    #[clap(long, default_value_t = 1.0, conflicts_with = "config")]
+    existential_threshold: f64,
 
-#[derive(Clone, PartialEq)]
-struct AppState {
-    file_name: Option<String>,
-    file_content: Option<String>,
-    existential_threshold_str: String, // Store as String
-    temporal_threshold_str: String,    // Store as String
-    classification_result: Option<Result<ClassificationOutput, AppError>>,
-    is_processing: bool,
+    /// Threshold of implications (defaults to --existential-threshold, like the
+    /// other existential relation thresholds)
+    #[clap(long, conflicts_with = "config")]
+    implication_threshold: Option<f64>,
+
+    /// Threshold of equivalences, reached by the implications in both directions
+    #[clap(long, conflicts_with = "config")]
+    equivalence_threshold: Option<f64>,
+
+    /// Threshold of negated equivalences
+    #[clap(long, conflicts_with = "config")]
+    negated_equivalence_threshold: Option<f64>,
+
+    /// Threshold of NAND relations (never together)
+    #[clap(long, conflicts_with = "config")]
+    nand_threshold: Option<f64>,
+
+    /// Threshold of OR relations (at least one of both in every trace)
+    #[clap(long, conflicts_with = "config")]
+    or_threshold: Option<f64>,
+
+    /// Threshold of repetitions (self-loops, see --self-loops)
+    #[clap(long, conflicts_with = "config")]
+    repetition_threshold: Option<f64>,
+
+    /// Threshold of temporal relations observed only directly (defaults to
+    /// --temporal-threshold)
+    #[clap(long, conflicts_with = "config")]
+    direct_threshold: Option<f64>,
+
+    /// Threshold of temporal relations observed at least once eventually (defaults to
+    /// --temporal-threshold)
+    #[clap(long, conflicts_with = "config")]
+    eventual_threshold: Option<f64>,
+
+    /// Minimum share of both orders for a parallel (concurrent or interleaved) temporal
+    /// relation, if neither order reaches its threshold; without it, such pairs have no
+    /// temporal dependency
+    #[clap(long, conflicts_with = "config")]
+    parallel_threshold: Option<f64>,
+
+    /// How the observations of a temporal relation decide between direct and eventual:
+    /// "any-eventual" (eventual as soon as one observation is), "majority" or
+    /// "direct-ratio=<ratio>" (direct if at least this share of observations is direct)
+    #[clap(long, default_value_t = TypeAggregation::AnyEventual, conflicts_with = "config")]
+    type_aggregation: TypeAggregation,
+
+    /// Include the self-loop of every activity (repeated occurrences within a trace) in
+    /// the matrix and count these cells in the classification
+    #[clap(long, conflicts_with = "config")]
+    self_loops: bool,
+
+    /// Event attribute forming the activity label of XES logs; repeat the flag
+    /// to combine several attributes like an XES classifier (values joined with "+")
+    #[clap(long = "activity-key", default_value = "concept:name", conflicts_with = "config")]
+    activity_keys: Vec<String>,
+
+    /// Name of a classifier declared in the XES log header to build activity labels
+    /// from (overrides --activity-key)
+    #[clap(long, conflicts_with = "config")]
+    classifier: Option<String>,
+
+    /// Print the classifiers declared in the XES log header and exit
+    #[clap(long)]
+    list_classifiers: bool,
+
+    /// Event attribute holding the timestamp of XES logs
+    #[clap(long, default_value = "time:timestamp", conflicts_with = "config")]
+    timestamp_key: String,
+
+    /// Keep XES events without timestamp (in document order) instead of dropping them
+    #[clap(long, conflicts_with = "config")]
+    keep_events_without_timestamp: bool,
+
+    /// Trace attribute holding the case id of XES logs
+    #[clap(long, default_value = "concept:name", conflicts_with = "config")]
+    case_key: String,
+
+    /// Pair start/complete lifecycle transitions of the events into activity instances and
+    /// compute temporal dependencies on their intervals (overlapping activities are unordered)
+    #[clap(long, conflicts_with = "config")]
+    lifecycle_intervals: bool,
+
+    /// How events of a case with identical timestamps are ordered: "document-order" (as
+    /// in the log file), "concurrent" (unordered, comparing events by timestamp and
+    /// recording the time gaps between related activities) or "attribute=<key>" (by the
+    /// value of this event attribute)
+    #[clap(long, default_value_t = TiePolicy::DocumentOrder, conflicts_with = "config")]
+    tie_policy: TiePolicy,
+
+    /// Stream XES logs trace by trace, keeping only the trace variants in memory
+    /// (for very large logs)
+    #[clap(long, conflicts_with_all = ["lifecycle_intervals", "config"])]
+    streaming: bool,
+
+    /// Object type to flatten OCEL logs on; by default every object type is classified
+    #[clap(long, conflicts_with = "config")]
+    object_type: Option<String>,
+
+    /// Case id column of CSV logs (files ending in .csv)
+    #[clap(long, default_value = "case:concept:name", conflicts_with = "config")]
+    csv_case_column: String,
+
+    /// Activity column of CSV logs
+    #[clap(long, default_value = "concept:name", conflicts_with = "config")]
+    csv_activity_column: String,
+
+    /// Timestamp column of CSV logs
+    #[clap(long, default_value = "time:timestamp", conflicts_with = "config")]
+    csv_timestamp_column: String,
+
+    /// Lifecycle transition column of CSV logs, defaults to "lifecycle:transition" if
+    /// the file has that column
+    #[clap(long, conflicts_with = "config")]
+    csv_lifecycle_column: Option<String>,
+
+    /// Field delimiter of CSV logs
+    #[clap(long, default_value_t = ',', conflicts_with = "config")]
+    csv_delimiter: char,
+
+    /// chrono format string of the CSV timestamp column (e.g. "%d.%m.%Y %H:%M"),
+    /// defaults to RFC 3339 / "%Y-%m-%d %H:%M:%S" / "%Y-%m-%d"
+    #[clap(long, conflicts_with = "config")]
+    csv_timestamp_format: Option<String>,
 }
 
-impl Default for AppState {
-    fn default() -> Self {
-        Self {
-            file_name: None,
-            file_content: None,
-            existential_threshold_str: "1.0".to_string(), // Default to "1.0" string
-            temporal_threshold_str: "1.0".to_string(),    // Default to "1.0" string
-            classification_result: None,
-            is_processing: false,
+impl Args {
+    fn input_source(&self) -> InputSource<'static> {
+        if self.file_path == "-" {
+            InputSource::Stdin
+        } else {
+            InputSource::path(&self.file_path)
         }
     }
-}
 
-fn parse_threshold_str(s: &str) -> Option<f64> {
-    s.parse::<f64>().ok().filter(|&val| (0.0..=1.0).contains(&val))
-}
+    /// The configuration of the analysis, from --config or the command line.
+    fn analysis_config(&self) -> Result<AnalysisConfig, Error> {
+        if let Some(path) = &self.config {
+            let json = std::fs::read_to_string(path)
+                .map_err(|e| Error::InvalidConfig(format!("{}: {}", path, e)))?;
+            return AnalysisConfig::from_json(&json);
+        }
 
-#[function_component(App)]
-fn app() -> Html {
-    let app_state_handle: UseStateHandle<AppState> = use_state(AppState::default);
-
-    let dispatch = {
-        let app_state_handle = app_state_handle.clone();
-        Rc::new(move |msg: AppMessage| {
-            let mut new_state = (*app_state_handle).clone();
-            match msg {
-                AppMessage::FileSelected(file_name_opt) => {
-                    if let Some(file_name) = file_name_opt {
-                        new_state.file_name = Some(file_name);
-                        new_state.file_content = None;
-                        new_state.classification_result = None;
-                    } else {
-                        new_state.file_name = None;
-                        new_state.file_content = None;
-                        new_state.classification_result = None;
-                    }
-                }
-                AppMessage::FileLoaded(result) => {
-                    match result {
-                        Ok(content) => new_state.file_content = Some(content),
-                        Err(e) => {
-                            new_state.classification_result =
-                                Some(Err(AppError::FileReadError(e)));
-                        }
-                    }
-                    new_state.is_processing = false;
-                }
-                AppMessage::ExistentialThresholdChanged(val_str) => {
-                    new_state.existential_threshold_str = val_str;
-                }
-                AppMessage::TemporalThresholdChanged(val_str) => {
-                    new_state.temporal_threshold_str = val_str;
-                }
-                AppMessage::ProcessLog => {
-                    new_state.is_processing = true;
-                    new_state.classification_result = None;
-                }
-                AppMessage::SetClassificationResult(result) => {
-                    new_state.classification_result = Some(result);
-                    new_state.is_processing = false;
-                }
-            }
-            app_state_handle.set(new_state);
-        })
-    };
+        let temporal_threshold = validate_threshold("Temporal", self.temporal_threshold)?;
+        let existential_threshold = validate_threshold("Existential", self.existential_threshold)?;
+        let config = AnalysisConfig {
+            thresholds: self.thresholds(existential_threshold, temporal_threshold)?,
+            parse: ParseOptions {
+                activity_keys: self.activity_keys.clone(),
+                classifier: self.classifier.clone(),
+                timestamp_key: self.timestamp_key.clone(),
+                keep_events_without_timestamp: self.keep_events_without_timestamp,
+                case_key: self.case_key.clone(),
+            },
+            csv: CsvImportOptions {
+                case_column: self.csv_case_column.clone(),
+                activity_column: self.csv_activity_column.clone(),
+                timestamp_column: self.csv_timestamp_column.clone(),
+                lifecycle_column: self.csv_lifecycle_column.clone(),
+                // Characters beyond one byte are not ASCII either, `validate` rejects both
+                delimiter: u8::try_from(self.csv_delimiter).unwrap_or(u8::MAX),
+                timestamp_format: self.csv_timestamp_format.clone(),
+            },
+            lifecycle_intervals: self.lifecycle_intervals,
+            tie_policy: self.tie_policy.clone(),
+            streaming: self.streaming,
+            object_type: self.object_type.clone(),
+            matrix: MatrixOptions {
+                include_self_loops: self.self_loops,
+            },
+            classification: ClassificationOptions {
+                count_self_loops: self.self_loops,
+            },
+            rule_set_version: RULE_SET_VERSION,
+        };
+        config.validate()?;
+        Ok(config)
+    }
 
-    let on_file_change = {
-        let dispatch = dispatch.clone();
-        Callback::from(move |e: Event| {
-            let input: HtmlInputElement = e.target_unchecked_into();
-            if let Some(files) = input.files() {
-                if let Some(file) = files.get(0) {
-                    let file_name = file.name();
-                    dispatch(AppMessage::FileSelected(Some(file_name)));
-
-                    let reader = FileReader::new().unwrap();
-                    let dispatch_clone = dispatch.clone();
-                    let onload = Closure::wrap(Box::new(move |e: ProgressEvent| {
-                        let reader: FileReader = e.target().unwrap().dyn_into().unwrap();
-                        let content = reader.result().unwrap().as_string().unwrap();
-                        dispatch_clone(AppMessage::FileLoaded(Ok(content)));
-                    }) as Box<dyn FnMut(_)>);
-
-                    reader.set_onload(Some(onload.as_ref().unchecked_ref()));
-                    reader.read_as_text(&file).unwrap();
-                    onload.forget(); // Prevent closure from being dropped
-                } else {
-                    dispatch(AppMessage::FileSelected(None));
-                }
-            }
+    /// The thresholds of the matrix generation, where the per-relation flags override
+    /// the existential and temporal threshold.
+    fn thresholds(
+        &self,
+        existential_threshold: f64,
+        temporal_threshold: f64,
+    ) -> Result<Thresholds, Error> {
+        let threshold = |name, value: Option<f64>, default| {
+            value.map_or(Ok(default), |value| validate_threshold(name, value))
+        };
+        Ok(Thresholds {
+            existential: ExistentialThresholds {
+                implication: threshold(
+                    "Implication",
+                    self.implication_threshold,
+                    existential_threshold,
+                )?,
+                equivalence: threshold(
+                    "Equivalence",
+                    self.equivalence_threshold,
+                    existential_threshold,
+                )?,
+                negated_equivalence: threshold(
+                    "Negated equivalence",
+                    self.negated_equivalence_threshold,
+                    existential_threshold,
+                )?,
+                nand: threshold("NAND", self.nand_threshold, existential_threshold)?,
+                or: threshold("OR", self.or_threshold, existential_threshold)?,
+                repetition: threshold(
+                    "Repetition",
+                    self.repetition_threshold,
+                    existential_threshold,
+                )?,
+            },
+            temporal: TemporalThresholds {
+                direct: threshold("Direct", self.direct_threshold, temporal_threshold)?,
+                eventual: threshold("Eventual", self.eventual_threshold, temporal_threshold)?,
+                parallel: self
+                    .parallel_threshold
+                    .map(|threshold| validate_threshold("Parallel", threshold))
+                    .transpose()?,
+                type_aggregation: self.type_aggregation,
+            },
         })
-    };
+    }
+}
 
-    let on_existential_threshold_change = {
-        let dispatch = dispatch.clone();
-        Callback::from(move |e: InputEvent| {
-            let input: HtmlInputElement = e.target_unchecked_into();
-            dispatch(AppMessage::ExistentialThresholdChanged(input.value()));
-        })
-    };
+fn main() {
+    let args = Args::parse();
 
-    let on_temporal_threshold_change = {
-        let dispatch = dispatch.clone();
-        Callback::from(move |e: InputEvent| {
-            let input: HtmlInputElement = e.target_unchecked_into();
-            dispatch(AppMessage::TemporalThresholdChanged(input.value()));
-        })
-    };
+    if let Err(e) = run(args) {
+        match e {
+            Error::InvalidThreshold { .. } | Error::InvalidConfig(_) => eprintln!("Error: {}", e),
+            e => eprintln!("Error parsing event log: {}", e),
+        }
+        std::process::exit(1);
+    }
+}
 
-    let on_process_log = {
-        let app_state_snapshot = (*app_state_handle).clone();
-        let dispatch = dispatch.clone();
-        Callback::from(move |_mouse_event: MouseEvent| {
-            // Parse and validate thresholds at the point of processing
-            let temp_thresh_opt = parse_threshold_str(&app_state_snapshot.temporal_threshold_str);
-            let ex_thresh_opt = parse_threshold_str(&app_state_snapshot.existential_threshold_str);
-
-            if app_state_snapshot.file_content.is_some()
-                && !app_state_snapshot.is_processing
-                && temp_thresh_opt.is_some()
-                && ex_thresh_opt.is_some()
-            {
-                dispatch(AppMessage::ProcessLog);
-
-                let content_clone = app_state_snapshot.file_content.clone().unwrap();
-                let temp_thresh_val = temp_thresh_opt.unwrap(); // Safe due to check above
-                let ex_thresh_val = ex_thresh_opt.unwrap();
-                let dispatch_clone = dispatch.clone();
-
-                spawn_local(async move {
-                    let result = {
-                        let traces_result = parse_into_traces(None, Some(&content_clone));
-                        traces_result
-                            .map_err(|e| AppError::XesParseError(e.to_string()))
-                            .and_then(|traces| {
-                                let matrix = generate_dependency_matrix(
-                                    &traces,
-                                    temp_thresh_val,
-                                    ex_thresh_val,
-                                );
-                                let classification_output = classify_matrix(&matrix);
-                                Ok(classification_output)
-                            })
-                    };
-                    dispatch_clone(AppMessage::SetClassificationResult(result));
-                });
-            }
-        })
-    };
+fn run(args: Args) -> Result<(), Error> {
+    let config = args.analysis_config()?;
 
-    let current_app_state_for_view = (*app_state_handle).clone();
-
-    // Determine button disabled state for the view
-    let is_temporal_thresh_valid = parse_threshold_str(&current_app_state_for_view.temporal_threshold_str).is_some();
-    let is_existential_thresh_valid = parse_threshold_str(&current_app_state_for_view.existential_threshold_str).is_some();
-    let is_process_button_disabled = current_app_state_for_view.file_content.is_none() || 
-                                     current_app_state_for_view.is_processing ||
-                                     !is_temporal_thresh_valid ||
-                                     !is_existential_thresh_valid;
-
-    html! {
-        <div class="container" style="padding: 20px; font-family: sans-serif;">
-            <h1>{ "Event Log Classifier" }</h1>
-
-            <div class="controls" style="margin-bottom: 20px; display: flex; gap: 20px; align-items: center;">
-                <div>
-                    <label for="xes-file" style="margin-right: 5px;">{ "Upload XES File:" }</label>
-                    <input type="file" id="xes-file" accept=".xes" onchange={on_file_change} />
-                    if let Some(name) = current_app_state_for_view.file_name {
-                        <p style="font-size: 0.9em; margin-top: 5px;">{ format!("Selected: {}", name) }</p>
-                    }
-                </div>
-            </div>
-
-            <div class="thresholds" style="margin-bottom: 20px; display: flex; gap: 30px;">
-                <div>
-                    <label for="temporal-threshold" style="margin-right: 5px;">{ "Temporal Threshold (0.0-1.0):" }</label>
-                    <input
-                        id="temporal-threshold"
-                        type="number" 
-                        min="0.0" max="1.0" step="0.05"
-                        value={current_app_state_for_view.temporal_threshold_str.clone()} // Bind to string state
-                        oninput={on_temporal_threshold_change}
-                        style={if !is_temporal_thresh_valid && !current_app_state_for_view.temporal_threshold_str.is_empty() {"width: 70px; border-color: red;"} else {"width: 70px;"} }
-                    />
-                </div>
-                <div>
-                    <label for="existential-threshold" style="margin-right: 5px;">{ "Existential Threshold (0.0-1.0):" }</label>
-                    <input
-                        id="existential-threshold"
-                        type="number"
-                        min="0.0" max="1.0" step="0.05"
-                        value={current_app_state_for_view.existential_threshold_str.clone()} // Bind to string state
-                        oninput={on_existential_threshold_change}
-                        style={if !is_existential_thresh_valid && !current_app_state_for_view.existential_threshold_str.is_empty() {"width: 70px; border-color: red;"} else {"width: 70px;"} }
-                    />
-                </div>
-            </div>
-
-            <button
-                onclick={on_process_log}
-                disabled={is_process_button_disabled}
-                style="padding: 10px 15px; font-size: 1em; cursor: pointer;"
-            >
-                { if current_app_state_for_view.is_processing { "Processing..." } else { "Process Log" } }
-            </button>
-
-            { // Display classification result
-                if let Some(result) = &current_app_state_for_view.classification_result {
-                    match result {
-                        Ok(output) => html! {
-                            <div class="result" style="margin-top: 20px; padding: 15px; border: 1px solid #ccc; border-radius: 5px;">
-                                <h2 style="margin-top: 0;">{ "Classification Result" }</h2>
-                                <p><b>{ "Classification:" }</b> { &output.classification.to_string() }</p>
-                                <h3>{ "Matched Rules:" }</h3>
-                                <ul>
-                                    { for output.matched_rules.iter().map(|rule| html!{ <li>{ rule }</li> }) }
-                                </ul>
-                            </div>
-                        },
-                        Err(e) => html! {
-                            <div class="error" style="color: red; margin-top: 20px;">
-                                { format!("Error: {}", e) }
-                            </div>
-                        }
-                    }
-                } else {
-                    html!{}
-                }
-            }
-        </div>
+    if args.list_classifiers {
+        for classifier in list_classifiers(args.input_source())? {
+            println!("{}: {}", classifier.name, classifier.keys.join(" "));
+        }
+        return Ok(());
     }
-}
 
-fn main() {
-    let args = Args::parse();
+    let analyses = analyze(&args.file_path, args.input_source(), &config)?;
 
-    if args.file_path.is_some() {
-        let file_path = args.file_path.unwrap();
-        let temporal_threshold = args.temporal_threshold;
-        let existential_threshold = args.existential_threshold;
+    for analysis in &analyses {
+        if let Some(object_type) = &analysis.object_type {
+            println!("Object Type: {}", object_type);
+        }
 
-        if !(0.0..=1.0).contains(&temporal_threshold) {
-            eprintln!("Error: Temporal threshold must be between 0.0 and 1.0");
-            std::process::exit(1);
+        let (matrix, classification_output) = (&analysis.matrix, &analysis.output);
+        println!("Classification: {}", classification_output.classification);
+        println!("Matched Rules: {:?}", classification_output.matched_rules);
+        println!(
+            "Temporal Type Aggregation: {}",
+            config.thresholds.temporal.type_aggregation
+        );
+        println!("Tie Policy: {}", config.tie_policy);
+        if let Some(parse_report) = &analysis.parse_report {
+            println!("Parse Report: {}", parse_report);
         }
 
-        if !(0.0..=1.0).contains(&existential_threshold) {
-            eprintln!("Error: Existential threshold must be between 0.0 and 1.0");
-            std::process::exit(1);
+        if args.print_ratios {
+            match CalculatedPercentages::new_with_options(matrix, &config.classification) {
+                Ok(percentages) => {
+                    println!("Calculated Percentages:");
+                    println!("{:?}", percentages);
+                }
+                Err(e) => {
+                    eprintln!("Error calculating percentages: {}", e);
+                }
+            }
         }
 
-        match parse_into_traces(Some(&file_path), None) {
-            Ok(traces) => {
-                let matrix =
-                    generate_dependency_matrix(&traces, temporal_threshold, existential_threshold);
-                let classification_output = classify_matrix(&matrix);
-                println!(
-                    "Classification: {}",
-                    classification_output.classification.to_string()
-                );
-                println!("Matched Rules: {:?}", classification_output.matched_rules);
-
-                if args.print_ratios {
-                    match CalculatedPercentages::new(&matrix) {
-                        Ok(percentages) => {
-                            println!("Calculated Percentages:");
-                            println!("{:?}", percentages);
-                        }
-                        Err(e) => {
-                            eprintln!("Error calculating percentages: {}", e);
-                        }
+        if args.print_dependencies {
+            println!("Dependencies:");
+            for (from_id, to_id, cell) in matrix.iter() {
+                let (from, to) = (matrix.activities().name(from_id), matrix.activities().name(to_id));
+                let dependency = cell.to_dependency(from, to);
+                match matrix.statistics(from_id, to_id) {
+                    Some(statistics) => {
+                        println!("{} -> {}: {} ({})", from, to, dependency, statistics)
                     }
+                    None => println!("{} -> {}: {}", from, to, dependency),
                 }
             }
-            Err(e) => {
-                eprintln!("Error parsing XES file: {}", e);
+        }
+    }
+
+    if let Some(path) = &args.export_matrix {
+        for analysis in &analyses {
+            let path = match &analysis.object_type {
+                Some(object_type) if analyses.len() > 1 => object_type_path(path, object_type),
+                _ => path.clone(),
+            };
+            let written = std::fs::File::create(&path)
+                .map_err(csv::Error::from)
+                .and_then(|file| analysis.matrix.write_csv(std::io::BufWriter::new(file)));
+            if let Err(e) = written {
+                eprintln!("Error writing matrix {}: {}", path, e);
                 std::process::exit(1);
             }
         }
-    } else if args.print_ratios {
-        eprintln!("Error: --file-path is required when using --print-ratios in CLI mode.");
-        std::process::exit(1);
-    } else {
-        yew::Renderer::<App>::new().render();
     }
-}
\ No newline at end of file
+
+    if let Some(path) = &args.manifest {
+        let manifests: Vec<&Manifest> =
+            analyses.iter().map(|analysis| &analysis.manifest).collect();
+        let json =
+            serde_json::to_string_pretty(&manifests).expect("manifests are always serializable");
+        if path == "-" {
+            println!("{}", json);
+        } else if let Err(e) = std::fs::write(path, json + "\n") {
+            eprintln!("Error writing manifest {}: {}", path, e);
+            std::process::exit(1);
+        }
+    }
+
+    Ok(())
+}
+
+/// `path` with the object type inserted before the file extension, e.g.
+/// "matrix.order.csv" for "matrix.csv".
+fn object_type_path(path: &str, object_type: &str) -> String {
+    let path = std::path::Path::new(path);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let file_name = match path.extension() {
+        Some(extension) => format!("{}.{}.{}", stem, object_type, extension.to_string_lossy()),
+        None => format!("{}.{}", stem, object_type),
+    };
+    path.with_file_name(file_name)
+        .to_string_lossy()
+        .into_owned()
+}
@@ -1,71 +1,347 @@
-use std::collections::{HashMap, HashSet};
-
+use crate::classification::InputMatrix;
+use crate::dependency_matrix::{DependencyCell, DependencyMatrix};
 use crate::dependency_types::{
-    dependency::Dependency,
-    existential::check_existential_dependency,
-    temporal::check_temporal_dependency,
+    dependency::DependencyStatistics,
+    existential::{
+        classify_cooccurrence, classify_repetition, CooccurrenceCounts, ExistentialDependency,
+        ExistentialThresholds,
+    },
+    temporal::{
+        classify_dependencies, trace_interval_observations, trace_time_gaps, Interval,
+        RelationCounts, TemporalThresholds, TimeGaps, TypeAggregation,
+    },
 };
-use crate::classification::{Activity, InputMatrix};
+use crate::error::{validate_threshold, Error};
+use crate::event_log::{ActivityId, ActivityInstance, EventLog, TraceView};
+use crate::occurrence_index::OccurrenceIndex;
+use crate::parallel;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Options of the matrix generation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatrixOptions {
+    /// Also compute the diagonal: the self-loop of every activity, temporally from its
+    /// repeated occurrences within a trace and existentially as
+    /// [`crate::dependency_types::existential::DependencyType::Repetition`].
+    pub include_self_loops: bool,
+}
+
+/// Thresholds of the matrix generation, per existential relation and per temporal
+/// relation type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Thresholds {
+    pub existential: ExistentialThresholds,
+    pub temporal: TemporalThresholds,
+}
+
+impl Thresholds {
+    /// One threshold for all existential and one for all temporal relations.
+    pub fn uniform(existential_threshold: f64, temporal_threshold: f64) -> Self {
+        Self {
+            existential: ExistentialThresholds::uniform(existential_threshold),
+            temporal: TemporalThresholds::uniform(temporal_threshold),
+        }
+    }
 
-pub fn generate_dependency_matrix(
-    traces: &[Vec<String>],
+    /// Checks that every threshold lies in `[0.0, 1.0]`.
+    pub fn validate(&self) -> Result<(), Error> {
+        let ExistentialThresholds { implication, equivalence, negated_equivalence, nand, or, repetition } =
+            self.existential;
+        let TemporalThresholds { direct, eventual, parallel, type_aggregation } = self.temporal;
+        validate_threshold("Implication", implication)?;
+        validate_threshold("Equivalence", equivalence)?;
+        validate_threshold("Negated equivalence", negated_equivalence)?;
+        validate_threshold("NAND", nand)?;
+        validate_threshold("OR", or)?;
+        validate_threshold("Repetition", repetition)?;
+        validate_threshold("Direct", direct)?;
+        validate_threshold("Eventual", eventual)?;
+        if let Some(parallel) = parallel {
+            validate_threshold("Parallel", parallel)?;
+        }
+        if let TypeAggregation::DirectRatio(ratio) = type_aggregation {
+            validate_threshold("Direct ratio", ratio)?;
+        }
+        Ok(())
+    }
+}
+
+/// Generates the dependency matrix from the activity sequences of a log, e.g. an
+/// [`crate::EventLog`], a [`crate::VariantLog`] or plain traces (`Vec<Vec<String>>`).
+///
+/// Map based counterpart of [`generate_indexed_dependency_matrix`].
+pub fn generate_dependency_matrix<T: TraceView + ?Sized>(
+    traces: &T,
     existential_threshold: f64,
     temporal_threshold: f64,
 ) -> InputMatrix {
-    let mut matrix: InputMatrix = HashMap::new();
+    generate_indexed_dependency_matrix(
+        traces,
+        existential_threshold,
+        temporal_threshold,
+        &MatrixOptions::default(),
+    )
+    .to_input_matrix()
+}
 
-    if traces.is_empty() {
-        return matrix;
-    }
+/// Generates the dense [`DependencyMatrix`] from the activity sequences of a log.
+///
+/// The log is reduced to its trace variants, weighted by their number of traces, and
+/// indexed in a single pass; the relations of all activity pairs are then read from the
+/// occurrence index. This gives the same result as running
+/// [`crate::dependency_types::temporal::check_temporal_dependency`] and
+/// [`crate::dependency_types::existential::check_existential_dependency`] on every pair.
+/// The matrix records the [`DependencyStatistics`] every cell was decided on.
+pub fn generate_indexed_dependency_matrix<T: TraceView + ?Sized>(
+    traces: &T,
+    existential_threshold: f64,
+    temporal_threshold: f64,
+    options: &MatrixOptions,
+) -> DependencyMatrix {
+    generate_indexed_dependency_matrix_with_thresholds(
+        traces,
+        &Thresholds::uniform(existential_threshold, temporal_threshold),
+        options,
+    )
+}
+
+/// Works like [`generate_indexed_dependency_matrix`] with a separate threshold for each
+/// relation.
+pub fn generate_indexed_dependency_matrix_with_thresholds<T: TraceView + ?Sized>(
+    traces: &T,
+    thresholds: &Thresholds,
+    options: &MatrixOptions,
+) -> DependencyMatrix {
+    ordered_dependency_matrix(traces, thresholds, options, |index| {
+        vec![None; index.activity_count() * index.activity_count()]
+    })
+}
+
+/// Works like [`generate_indexed_dependency_matrix_with_thresholds`] on the cases of an
+/// event log in their order.
+///
+/// Besides the counts, the statistics of every cell record the [`TimeGaps`] between the
+/// timestamps of the paired occurrences; occurrences without timestamp are left out.
+pub fn generate_indexed_event_log_dependency_matrix_with_thresholds(
+    event_log: &EventLog,
+    thresholds: &Thresholds,
+    options: &MatrixOptions,
+) -> DependencyMatrix {
+    ordered_dependency_matrix(event_log, thresholds, options, |index| {
+        let size = index.activity_count();
+        let ids: HashMap<&str, usize> = (0..size)
+            .map(|id| (index.name(id as ActivityId), id))
+            .collect();
+        let mut gaps = vec![Vec::new(); size * size];
+        for case in &event_log.cases {
+            trace_time_gaps(&case.timed_activity_trace(), options.include_self_loops, |from, to, gap| {
+                gaps[ids[from] * size + ids[to]].push(gap)
+            });
+        }
+        gaps.into_iter().map(TimeGaps::from_gaps).collect()
+    })
+}
 
-    let activities_set: HashSet<Activity> = traces
+/// The ordered dependency matrix of `traces`, with the time gaps of every pair (row by
+/// row, as indexed by the occurrence index) given by `time_gaps`.
+fn ordered_dependency_matrix<T: TraceView + ?Sized>(
+    traces: &T,
+    thresholds: &Thresholds,
+    options: &MatrixOptions,
+    time_gaps: impl FnOnce(&OccurrenceIndex) -> Vec<Option<TimeGaps>>,
+) -> DependencyMatrix {
+    let variants = traces.trace_variants();
+    let index = OccurrenceIndex::new(&variants);
+    let relation_counts = index.relation_counts(options.include_self_loops);
+    let time_gaps = time_gaps(&index);
+
+    build_matrix(&index, options, |from, to| {
+        let (from_activity, to_activity) = (index.name(from), index.name(to));
+
+        let temporal_counts = relation_counts[from as usize * index.activity_count() + to as usize];
+        let temporal_dependency =
+            classify_dependencies(from_activity, to_activity, &temporal_counts, &thresholds.temporal);
+
+        let existential_counts = index.cooccurrence(from, to);
+        let existential_dependency =
+            existential_dependency(from_activity, to_activity, &existential_counts, &thresholds.existential);
+
+        let statistics = DependencyStatistics {
+            temporal: temporal_counts,
+            existential: existential_counts,
+            time_gaps: time_gaps[from as usize * index.activity_count() + to as usize],
+        };
+        (DependencyCell::new(temporal_dependency.as_ref(), existential_dependency.as_ref()), statistics)
+    })
+}
+
+/// Generates the dependency matrix from activity instances with start and end times
+/// (see [`crate::parser::parse_into_activity_instances`] or [`crate::EventLog::activity_instances`]).
+///
+/// Existential dependencies are the same as for [`generate_dependency_matrix`], temporal
+/// dependencies are computed on the intervals, so concurrent instances are not ordered.
+pub fn generate_interval_dependency_matrix(
+    traces: &[Vec<ActivityInstance>],
+    existential_threshold: f64,
+    temporal_threshold: f64,
+) -> InputMatrix {
+    generate_indexed_interval_dependency_matrix(
+        traces,
+        existential_threshold,
+        temporal_threshold,
+        &MatrixOptions::default(),
+    )
+    .to_input_matrix()
+}
+
+/// Generates the dense [`DependencyMatrix`] from activity instances with start and end
+/// times, see [`generate_interval_dependency_matrix`].
+pub fn generate_indexed_interval_dependency_matrix(
+    traces: &[Vec<ActivityInstance>],
+    existential_threshold: f64,
+    temporal_threshold: f64,
+    options: &MatrixOptions,
+) -> DependencyMatrix {
+    generate_indexed_interval_dependency_matrix_with_thresholds(
+        traces,
+        &Thresholds::uniform(existential_threshold, temporal_threshold),
+        options,
+    )
+}
+
+/// Works like [`generate_indexed_interval_dependency_matrix`] with a separate threshold
+/// for each relation.
+///
+/// Besides the counts, the statistics of every cell record the
+/// [`TimeGaps`](crate::dependency_types::temporal::TimeGaps) of the ordered observations.
+pub fn generate_indexed_interval_dependency_matrix_with_thresholds(
+    traces: &[Vec<ActivityInstance>],
+    thresholds: &Thresholds,
+    options: &MatrixOptions,
+) -> DependencyMatrix {
+    let intervals: Vec<Vec<Interval>> = traces
         .iter()
-        .flat_map(|trace| trace.iter().cloned())
+        .map(|trace| {
+            trace
+                .iter()
+                .map(|instance| Interval {
+                    activity: &instance.activity,
+                    start: instance.start,
+                    end: instance.end,
+                })
+                .collect()
+        })
         .collect();
-    
-    let activities_sorted: Vec<&Activity> = {
-        let mut sorted: Vec<&Activity> = activities_set.iter().collect();
-        sorted.sort();
-        sorted
-    };
-
     let traces_str: Vec<Vec<&str>> = traces
         .iter()
-        .map(|trace| trace.iter().map(|s| s.as_str()).collect())
+        .map(|trace| {
+            trace
+                .iter()
+                .map(|instance| instance.activity.as_str())
+                .collect()
+        })
         .collect();
+    let variants = traces_str.trace_variants();
+    let index = OccurrenceIndex::new(&variants);
 
-    for from_activity_ref in &activities_sorted {
-        for to_activity_ref in &activities_sorted {
-            let from_activity = (*from_activity_ref).clone();
-            let to_activity = (*to_activity_ref).clone();
-
-            // Skip self-loops for now
-            if from_activity == to_activity {
-                continue;
+    // The temporal observations of all pairs, row by row, in one pass over the traces
+    let size = index.activity_count();
+    let ids: HashMap<&str, usize> = (0..size)
+        .map(|id| (index.name(id as ActivityId), id))
+        .collect();
+    let mut observations = vec![(RelationCounts::default(), Vec::new()); size * size];
+    for trace in &intervals {
+        trace_interval_observations(trace, options.include_self_loops, |from, to, relation| {
+            let (counts, gaps) = &mut observations[ids[from] * size + ids[to]];
+            match relation {
+                Some((relation, gap)) => {
+                    counts.add(relation, 1);
+                    gaps.push(gap);
+                }
+                None => counts.unordered += 1,
             }
+        });
+    }
+    let observations: Vec<(RelationCounts, Option<TimeGaps>)> = observations
+        .into_iter()
+        .map(|(counts, gaps)| (counts, TimeGaps::from_gaps(gaps)))
+        .collect();
 
-            let temporal_dependency = check_temporal_dependency(
-                &from_activity,
-                &to_activity,
-                &traces_str,
-                temporal_threshold,
-            );
+    build_matrix(&index, options, |from, to| {
+        let (from_activity, to_activity) = (index.name(from), index.name(to));
 
-            let existential_dependency = check_existential_dependency(
-                &from_activity,
-                &to_activity,
-                &traces_str,
-                existential_threshold,
-            );
+        let (temporal_counts, time_gaps) = observations[from as usize * size + to as usize];
+        let temporal_dependency = classify_dependencies(
+            from_activity,
+            to_activity,
+            &temporal_counts,
+            &thresholds.temporal,
+        );
 
-            let dependency_obj = Dependency::new(
-                from_activity.clone(),
-                to_activity.clone(),
-                temporal_dependency,
-                existential_dependency,
-            );
-            
-            matrix.insert((from_activity, to_activity), dependency_obj);
+        let existential_counts = index.cooccurrence(from, to);
+        let existential_dependency = existential_dependency(
+            from_activity,
+            to_activity,
+            &existential_counts,
+            &thresholds.existential,
+        );
+
+        let statistics = DependencyStatistics {
+            temporal: temporal_counts,
+            existential: existential_counts,
+            time_gaps,
+        };
+        (
+            DependencyCell::new(
+                temporal_dependency.as_ref(),
+                existential_dependency.as_ref(),
+            ),
+            statistics,
+        )
+    })
+}
+
+/// The existential dependency of a pair of activities, or the repetition of an activity
+/// on the diagonal.
+fn existential_dependency(
+    from: &str,
+    to: &str,
+    counts: &CooccurrenceCounts,
+    thresholds: &ExistentialThresholds,
+) -> Option<ExistentialDependency> {
+    if from == to {
+        return classify_repetition(from, counts.from, counts.repeated, thresholds.repetition);
+    }
+    classify_cooccurrence(from, to, counts, thresholds)
+}
+
+/// Computes the dependency and statistics of every ordered pair of distinct activities
+/// in `index`, and of every activity with itself if self-loops are included.
+/// Activities are indexed in alphabetical order.
+///
+/// With the `parallel` feature the rows are computed on all cores (on native targets),
+/// which gives the same matrix as the sequential computation.
+fn build_matrix<F>(index: &OccurrenceIndex, options: &MatrixOptions, check_pair: F) -> DependencyMatrix
+where
+    F: Fn(ActivityId, ActivityId) -> (DependencyCell, DependencyStatistics) + Sync + Send,
+{
+    let activity_count = index.activity_count();
+    let rows = parallel::map_indices(activity_count, |from| {
+        let from = from as ActivityId;
+        (0..activity_count as ActivityId)
+            .filter(|&to| to != from || options.include_self_loops)
+            .map(|to| (to, check_pair(from, to)))
+            .collect::<Vec<_>>()
+    });
+
+    let mut matrix = DependencyMatrix::new(index.activity_table());
+    for (from, row) in rows.into_iter().enumerate() {
+        for (to, (cell, statistics)) in row {
+            matrix.insert(from as ActivityId, to, cell);
+            matrix.insert_statistics(from as ActivityId, to, statistics);
         }
     }
     matrix
@@ -132,4 +408,137 @@ mod tests {
         let matrix = generate_dependency_matrix(&traces, 1.0, 1.0);
         assert!(matrix.is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_variant_log_gives_same_matrix() {
+        let traces = vec![
+            vec!["A", "B", "C"],
+            vec!["A", "C", "B"],
+            vec!["A", "B", "C"],
+            vec!["A", "B", "C"],
+            vec!["D", "A", "C"],
+        ];
+        let variants = crate::VariantLog::from_traces(&traces);
+        for threshold in [0.5, 0.6, 0.8, 1.0] {
+            assert_eq!(
+                generate_dependency_matrix(&variants, threshold, threshold),
+                generate_dependency_matrix(&traces, threshold, threshold)
+            );
+        }
+    }
+
+    #[test]
+    fn test_indexed_matrix_matches_pairwise_checkers() {
+        use crate::dependency_types::{
+            existential::{
+                check_existential_dependency, check_existential_dependency_with_thresholds,
+                cooccurrence_counts,
+            },
+            temporal::{
+                check_temporal_dependency, check_temporal_dependency_with_thresholds,
+                temporal_relation_counts,
+            },
+        };
+        let traces = vec![
+            vec!["A", "B", "A", "C", "B"],
+            vec!["C", "A", "B"],
+            vec!["A", "B", "A", "C", "B"],
+            vec!["B", "B", "D"],
+            vec!["D"],
+        ];
+        for threshold in [0.3, 0.5, 0.7, 1.0] {
+            let matrix = generate_dependency_matrix(&traces, threshold, threshold);
+            assert_eq!(matrix.len(), 12);
+            for ((from, to), dependency) in &matrix {
+                assert_eq!(
+                    dependency.temporal_dependency,
+                    check_temporal_dependency(from, to, &traces, threshold)
+                );
+                assert_eq!(
+                    dependency.existential_dependency,
+                    check_existential_dependency(from, to, &traces, threshold)
+                );
+                assert_eq!(
+                    dependency.statistics,
+                    Some(DependencyStatistics {
+                        temporal: temporal_relation_counts(from, to, &traces),
+                        existential: cooccurrence_counts(from, to, &traces),
+                        time_gaps: None,
+                    })
+                );
+            }
+        }
+
+        let thresholds = Thresholds {
+            existential: ExistentialThresholds { implication: 0.5, negated_equivalence: 0.9, ..ExistentialThresholds::uniform(0.7) },
+            temporal: TemporalThresholds { direct: 0.4, eventual: 0.8, parallel: Some(0.3), type_aggregation: TypeAggregation::Majority },
+        };
+        let matrix = generate_indexed_dependency_matrix_with_thresholds(&traces, &thresholds, &MatrixOptions::default());
+        for (from, to, cell) in matrix.iter() {
+            let (from, to) = (matrix.activities().name(from), matrix.activities().name(to));
+            let dependency = cell.to_dependency(from, to);
+            assert_eq!(
+                dependency.temporal_dependency,
+                check_temporal_dependency_with_thresholds(from, to, &traces, &thresholds.temporal)
+            );
+            assert_eq!(
+                dependency.existential_dependency,
+                check_existential_dependency_with_thresholds(from, to, &traces, &thresholds.existential)
+            );
+        }
+    }
+
+    #[test]
+    fn test_indexed_matrix_with_self_loops() {
+        use crate::dependency_types::{
+            existential::check_existential_dependency, temporal::check_temporal_dependency,
+        };
+        let traces = vec![
+            vec!["A", "B", "A"],
+            vec!["A", "A", "B"],
+            vec!["B", "A"],
+        ];
+        let options = MatrixOptions { include_self_loops: true };
+        let matrix = generate_indexed_dependency_matrix(&traces, 0.6, 0.6, &options);
+        assert_eq!(matrix.len(), 4);
+        let cell = matrix.get_by_name("B", "B").unwrap();
+        assert_eq!((cell.temporal, cell.existential), (None, None));
+
+        // A repeats in two of the three traces, once adjacent and once not
+        let cell = matrix.get_by_name("A", "A").unwrap();
+        assert_eq!(cell.existential, Some((ExisEnum::Repetition, ExisDir::Both)));
+        assert_eq!(cell.temporal, Some((TempEnum::Eventual, TempDir::Forward)));
+        let dependency = cell.to_dependency("A", "A");
+        assert_eq!(dependency.temporal_dependency, check_temporal_dependency("A", "A", &traces, 0.6));
+        assert_eq!(dependency.existential_dependency, check_existential_dependency("A", "A", &traces, 0.6));
+
+        assert_eq!(
+            generate_indexed_dependency_matrix(&traces, 0.6, 0.6, &MatrixOptions::default()).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_generate_interval_matrix_overlapping_activities() {
+        use chrono::{TimeZone, Utc};
+        let at = |second| Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, second).unwrap();
+        let instance = |activity: &str, start, end| {
+            ActivityInstance::new(activity.to_string(), at(start), at(end))
+        };
+        // A and B always overlap, C always follows both
+        let traces = vec![
+            vec![instance("A", 1, 3), instance("B", 2, 4), instance("C", 5, 5)],
+            vec![instance("B", 1, 3), instance("A", 2, 4), instance("C", 5, 5)],
+        ];
+        let matrix = generate_interval_dependency_matrix(&traces, 1.0, 1.0);
+        assert_eq!(matrix.len(), 6);
+
+        let dep_ab = matrix.get(&("A".to_string(), "B".to_string())).unwrap();
+        assert_eq!(dep_ab.temporal_dependency, None);
+        assert_eq!(dep_ab.existential_dependency.as_ref().unwrap().dependency_type, ExisEnum::Equivalence);
+
+        let dep_ac = matrix.get(&("A".to_string(), "C".to_string())).unwrap();
+        assert_eq!(dep_ac.temporal_dependency.as_ref().unwrap().dependency_type, TempEnum::Direct);
+        assert_eq!(dep_ac.temporal_dependency.as_ref().unwrap().direction, TempDir::Forward);
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::dependency_types::{
+    existential::CooccurrenceCounts,
+    temporal::{pair_position_relations, self_loop_relations, RelationCounts},
+    WeightedTrace,
+};
+use crate::event_log::{ActivityId, ActivityTable};
+use crate::parallel;
+
+/// Set of trace (variant) indices, one bit per trace.
+#[derive(Debug, Clone, Default)]
+struct TraceSet {
+    words: Vec<u64>,
+}
+
+impl TraceSet {
+    fn insert(&mut self, trace: usize) {
+        let word = trace / 64;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= 1 << (trace % 64);
+    }
+
+    /// The traces contained in the set.
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.intersection(self)
+    }
+
+    /// The traces contained in both sets.
+    fn intersection<'a>(&'a self, other: &'a TraceSet) -> impl Iterator<Item = usize> + 'a {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .enumerate()
+            .flat_map(|(word_index, (&a, &b))| {
+                let mut word = a & b;
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    Some(word_index * 64 + bit)
+                })
+            })
+    }
+}
+
+/// Occurrences of every activity, built in a single pass over the (weighted) traces.
+///
+/// Stores for every trace the positions of each activity it contains, and for every
+/// activity the set of traces containing it, so the relations of all activity pairs
+/// can be derived without rescanning the traces for each pair.
+pub(crate) struct OccurrenceIndex<'a> {
+    /// Activity names, sorted alphabetically, indexed by [`ActivityId`].
+    activities: Vec<&'a str>,
+    weights: Vec<usize>,
+    total_weight: usize,
+    /// Per trace: the activities it contains (ascending ids) with their positions.
+    occurrences: Vec<Vec<(ActivityId, Vec<usize>)>>,
+    /// Per activity: the traces containing it and their total weight.
+    traces_with: Vec<(TraceSet, usize)>,
+    /// Per activity: the total weight of the traces containing it more than once.
+    repeated_in: Vec<usize>,
+}
+
+impl<'a> OccurrenceIndex<'a> {
+    pub(crate) fn new<T: WeightedTrace>(traces: &'a [T]) -> Self {
+        let activities: Vec<&str> = traces
+            .iter()
+            .flat_map(|trace| trace.activities().iter().copied())
+            .collect::<BTreeSet<&str>>()
+            .into_iter()
+            .collect();
+        let ids: HashMap<&str, ActivityId> = activities
+            .iter()
+            .enumerate()
+            .map(|(id, &activity)| (activity, id as ActivityId))
+            .collect();
+
+        let mut traces_with = vec![(TraceSet::default(), 0); activities.len()];
+        let mut repeated_in = vec![0; activities.len()];
+        let mut occurrences = Vec::with_capacity(traces.len());
+        for (trace_index, trace) in traces.iter().enumerate() {
+            let mut positions: HashMap<ActivityId, Vec<usize>> = HashMap::new();
+            for (position, activity) in trace.activities().iter().enumerate() {
+                positions.entry(ids[activity]).or_default().push(position);
+            }
+            let mut positions: Vec<(ActivityId, Vec<usize>)> = positions.into_iter().collect();
+            positions.sort_unstable_by_key(|(id, _)| *id);
+
+            for (id, activity_positions) in &positions {
+                let (set, weight) = &mut traces_with[*id as usize];
+                set.insert(trace_index);
+                *weight += trace.weight();
+                if activity_positions.len() > 1 {
+                    repeated_in[*id as usize] += trace.weight();
+                }
+            }
+            occurrences.push(positions);
+        }
+
+        let weights: Vec<usize> = traces.iter().map(WeightedTrace::weight).collect();
+        Self {
+            activities,
+            total_weight: weights.iter().sum(),
+            weights,
+            occurrences,
+            traces_with,
+            repeated_in,
+        }
+    }
+
+    pub(crate) fn activity_count(&self) -> usize {
+        self.activities.len()
+    }
+
+    pub(crate) fn name(&self, id: ActivityId) -> &'a str {
+        self.activities[id as usize]
+    }
+
+    /// The activities as a table, with the same ids as in the index.
+    pub(crate) fn activity_table(&self) -> ActivityTable {
+        let mut table = ActivityTable::default();
+        for activity in &self.activities {
+            table.intern(activity);
+        }
+        table
+    }
+
+    /// Number of traces (weighted) containing `from`, `to` and both, out of all traces,
+    /// and containing `from` more than once.
+    pub(crate) fn cooccurrence(&self, from: ActivityId, to: ActivityId) -> CooccurrenceCounts {
+        let (from_set, from_count) = &self.traces_with[from as usize];
+        let (to_set, to_count) = &self.traces_with[to as usize];
+        CooccurrenceCounts {
+            from: *from_count,
+            to: *to_count,
+            both: from_set
+                .intersection(to_set)
+                .map(|trace| self.weights[trace])
+                .sum(),
+            total: self.total_weight,
+            repeated: self.repeated_in[from as usize],
+        }
+    }
+
+    /// The temporal relations of every ordered pair of activities, row by row
+    /// (`from * activity_count + to`). Each row is collected from the traces containing
+    /// `from` (rows are split over threads with the `parallel` feature). The diagonal is
+    /// only filled with `include_self_loops`.
+    pub(crate) fn relation_counts(&self, include_self_loops: bool) -> Vec<RelationCounts> {
+        let size = self.activities.len();
+        let rows = parallel::map_indices(size, |from| {
+            let mut row = vec![RelationCounts::default(); size];
+            for trace in self.traces_with[from].0.iter() {
+                let (positions, weight) = (&self.occurrences[trace], self.weights[trace]);
+                let from_positions =
+                    match positions.binary_search_by_key(&(from as ActivityId), |(id, _)| *id) {
+                        Ok(index) => &positions[index].1,
+                        Err(_) => continue,
+                    };
+                for (to, to_positions) in positions {
+                    let pair_counts = &mut row[*to as usize];
+                    let mut observe = |relation, _, _| pair_counts.add(relation, weight);
+                    if *to as usize != from {
+                        pair_position_relations(from_positions, to_positions, observe);
+                    } else if include_self_loops {
+                        self_loop_relations(from_positions, &mut observe);
+                    }
+                }
+            }
+            row
+        });
+        rows.concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_occurrence_index_counts() {
+        let traces = vec![
+            (vec!["B", "A", "B"], 2),
+            (vec!["A", "C"], 1),
+            (vec!["C"], 3),
+        ];
+        let index = OccurrenceIndex::new(&traces);
+        assert_eq!(index.activity_count(), 3);
+        assert_eq!(index.name(0), "A");
+
+        let counts = index.cooccurrence(0, 1);
+        assert_eq!(
+            (counts.from, counts.to, counts.both, counts.total),
+            (3, 2, 2, 6)
+        );
+        let counts = index.cooccurrence(1, 2);
+        assert_eq!((counts.from, counts.to, counts.both), (2, 4, 0));
+
+        // A is directly preceded and followed by B in the first variant
+        let relations = index.relation_counts(true);
+        let (a_b, a_c, b_c) = (relations[1], relations[2], relations[3 + 2]);
+        assert_eq!((a_b.forward_direct, a_b.backward_direct), (2, 2));
+        assert_eq!(a_c.forward_direct, 1);
+        assert_eq!(b_c, RelationCounts::default());
+        // B B in the first variant, C never repeats
+        let counts = index.cooccurrence(1, 1);
+        assert_eq!((counts.from, counts.repeated), (2, 2));
+        let counts = index.cooccurrence(2, 2);
+        assert_eq!((counts.from, counts.repeated), (4, 0));
+        assert_eq!(relations[3 + 1].forward_eventual, 2);
+        assert_eq!(relations[2 * 3 + 2], RelationCounts::default());
+        assert_eq!(
+            index.relation_counts(false)[3 + 1],
+            RelationCounts::default()
+        );
+    }
+}
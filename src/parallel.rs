@@ -0,0 +1,44 @@
+//! Work split over all cores with rayon on native targets with the `parallel` feature,
+//! and run sequentially otherwise (e.g. in the wasm build).
+//!
+//! Results keep the order of the indices, so the output does not depend on the number
+//! of threads.
+
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+mod imp {
+    use rayon::prelude::*;
+
+    pub(crate) fn map_indices<R, F>(count: usize, f: F) -> Vec<R>
+    where
+        R: Send,
+        F: Fn(usize) -> R + Sync + Send,
+    {
+        (0..count).into_par_iter().map(f).collect()
+    }
+}
+
+#[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+mod imp {
+    // Same bounds as the parallel version, so both build from the same callers
+    pub(crate) fn map_indices<R, F>(count: usize, f: F) -> Vec<R>
+    where
+        R: Send,
+        F: Fn(usize) -> R + Sync + Send,
+    {
+        (0..count).map(f).collect()
+    }
+}
+
+/// Computes `f(0), ..., f(count - 1)`.
+pub(crate) use imp::map_indices;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_indices_is_deterministic() {
+        let squares = map_indices(1000, |i| i * i);
+        assert_eq!(squares, (0..1000).map(|i| i * i).collect::<Vec<_>>());
+    }
+}
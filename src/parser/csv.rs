@@ -0,0 +1,333 @@
+use super::ParseError;
+use crate::error::Error;
+use crate::event_log::{is_complete, Attributes, Case, Event, EventLog, TraceView};
+use crate::input::InputSource;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Column names and formats used to read a tabular (CSV) event log.
+///
+/// Every row of the file is one event. Rows are grouped into traces by the
+/// case column and ordered by the timestamp column within each trace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvImportOptions {
+    /// Column holding the case identifier.
+    pub case_column: String,
+    /// Column holding the activity name.
+    pub activity_column: String,
+    /// Column holding the event timestamp.
+    pub timestamp_column: String,
+    /// Column holding the lifecycle transition (e.g. `start`, `complete`). If `None`,
+    /// the `lifecycle:transition` column is used when the file has one.
+    pub lifecycle_column: Option<String>,
+    /// Field delimiter, e.g. `b','` or `b';'`.
+    pub delimiter: u8,
+    /// `chrono` format string of the timestamp column (e.g. `"%d.%m.%Y %H:%M"`).
+    /// If `None`, RFC 3339, `%Y-%m-%d %H:%M:%S` and `%Y-%m-%d` are tried in that order.
+    pub timestamp_format: Option<String>,
+}
+
+impl Default for CsvImportOptions {
+    fn default() -> Self {
+        Self {
+            case_column: "case:concept:name".to_string(),
+            activity_column: "concept:name".to_string(),
+            timestamp_column: "time:timestamp".to_string(),
+            lifecycle_column: None,
+            delimiter: b',',
+            timestamp_format: None,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CsvParseError {
+    #[error("Failed to read CSV: {0}")]
+    Csv(#[from] ::csv::Error),
+    #[error("Column '{0}' not found in CSV header")]
+    MissingColumn(String),
+    #[error("Invalid timestamp '{value}' in line {line}")]
+    InvalidTimestamp { value: String, line: u64 },
+}
+
+/// Parses a timestamp cell, either with the given `chrono` format or with the default formats.
+fn parse_timestamp(value: &str, format: Option<&str>) -> Option<DateTime<Utc>> {
+    match format {
+        Some(format) => DateTime::parse_from_str(value, format)
+            .map(|date| date.with_timezone(&Utc))
+            .ok()
+            .or_else(|| {
+                NaiveDateTime::parse_from_str(value, format)
+                    .ok()
+                    .map(|date| date.and_utc())
+            })
+            .or_else(|| {
+                NaiveDate::parse_from_str(value, format)
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+                    .map(|date| date.and_utc())
+            }),
+        None => DateTime::parse_from_rfc3339(value)
+            .map(|date| date.with_timezone(&Utc))
+            .ok()
+            .or_else(|| parse_timestamp(value, Some("%Y-%m-%d %H:%M:%S%.f")))
+            .or_else(|| parse_timestamp(value, Some("%Y-%m-%d"))),
+    }
+}
+
+fn column_index(headers: &::csv::StringRecord, column: &str) -> Result<usize, CsvParseError> {
+    headers
+        .iter()
+        .position(|header| header.trim() == column)
+        .ok_or_else(|| CsvParseError::MissingColumn(column.to_string()))
+}
+
+/// Reads a CSV event log into activity traces.
+///
+/// Traces appear in the order in which their case is first seen in the file.
+/// Rows with an empty activity or timestamp cell are skipped, in the same way
+/// the XES parser drops events without `concept:name` or `time:timestamp`; a case
+/// whose rows are all skipped is kept as an empty trace, like an XES trace without
+/// events. Like for XES, only `complete` events are used if a trace has any.
+pub fn parse_csv_into_traces(
+    source: InputSource,
+    options: &CsvImportOptions,
+) -> Result<Vec<Vec<String>>, Error> {
+    let event_log = parse_csv_event_log(source, options)?;
+    Ok(event_log
+        .activity_traces()
+        .into_iter()
+        .map(|trace| trace.into_iter().map(str::to_string).collect())
+        .collect())
+}
+
+/// Reads a CSV event log into the typed [`EventLog`] model.
+///
+/// All cells of a row, including the case, activity and timestamp columns, are
+/// kept as event attributes under their header name. See [`parse_csv_into_traces`]
+/// for the ordering and skipping rules. Fails with [`Error::EmptyLog`] if no row is left.
+pub fn parse_csv_event_log(
+    source: InputSource,
+    options: &CsvImportOptions,
+) -> Result<EventLog, Error> {
+    let reader = ::csv::ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .trim(::csv::Trim::All)
+        .from_reader(source.into_buf_read()?);
+
+    read_event_log(reader, options)
+        .map_err(ParseError::from)?
+        .non_empty()
+}
+
+fn read_event_log<R: std::io::Read>(
+    mut reader: ::csv::Reader<R>,
+    options: &CsvImportOptions,
+) -> Result<EventLog, CsvParseError> {
+    let headers = reader.headers()?.clone();
+    let case_idx = column_index(&headers, &options.case_column)?;
+    let activity_idx = column_index(&headers, &options.activity_column)?;
+    let timestamp_idx = column_index(&headers, &options.timestamp_column)?;
+    let lifecycle_idx = match &options.lifecycle_column {
+        Some(column) => Some(column_index(&headers, column)?),
+        None => column_index(&headers, "lifecycle:transition").ok(),
+    };
+
+    let mut case_positions: HashMap<String, usize> = HashMap::new();
+    let mut cases: Vec<Case> = Vec::new();
+
+    for record in reader.records() {
+        let record = record?;
+        let case_id = record.get(case_idx).unwrap_or_default();
+        let activity = record.get(activity_idx).unwrap_or_default();
+        let timestamp = record.get(timestamp_idx).unwrap_or_default();
+        let lifecycle = lifecycle_idx
+            .and_then(|idx| record.get(idx))
+            .filter(|value| !value.is_empty());
+
+        let position = *case_positions
+            .entry(case_id.to_string())
+            .or_insert_with(|| {
+                cases.push(Case {
+                    id: case_id.to_string(),
+                    ..Case::default()
+                });
+                cases.len() - 1
+            });
+
+        if activity.is_empty() || timestamp.is_empty() {
+            cases[position].dropped_complete |= is_complete(lifecycle);
+            continue;
+        }
+
+        let date =
+            parse_timestamp(timestamp, options.timestamp_format.as_deref()).ok_or_else(|| {
+                CsvParseError::InvalidTimestamp {
+                    value: timestamp.to_string(),
+                    line: record.position().map_or(0, |position| position.line()),
+                }
+            })?;
+
+        let attributes: Attributes = headers
+            .iter()
+            .zip(record.iter())
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(header, value)| (header.trim().to_string(), value.to_string()))
+            .collect();
+
+        cases[position].events.push(Event {
+            activity: activity.to_string(),
+            timestamp: Some(date),
+            lifecycle: lifecycle.map(str::to_string),
+            attributes,
+        });
+    }
+
+    for case in &mut cases {
+        case.events.sort_by_key(|event| event.timestamp); // stable, keeps file order for equal timestamps
+    }
+
+    Ok(EventLog { cases })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv_groups_and_sorts_by_case() {
+        let content = "\
+case:concept:name,concept:name,time:timestamp
+1,A,2024-01-01T10:00:00+00:00
+2,A,2024-01-01T09:00:00+00:00
+1,C,2024-01-01T12:00:00+00:00
+1,B,2024-01-01T11:00:00+00:00
+2,B,2024-01-01T09:30:00+00:00
+";
+        let traces = parse_csv_into_traces(
+            InputSource::Bytes(content.as_bytes()),
+            &CsvImportOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(traces, vec![vec!["A", "B", "C"], vec!["A", "B"]]);
+    }
+
+    #[test]
+    fn test_parse_csv_keeps_cases_without_events() {
+        let content = "\
+case:concept:name,concept:name,time:timestamp
+1,,2024-01-01T10:00:00+00:00
+2,A,2024-01-01T09:00:00+00:00
+1,B,
+";
+        let event_log = parse_csv_event_log(
+            InputSource::Bytes(content.as_bytes()),
+            &CsvImportOptions::default(),
+        )
+        .unwrap();
+        // Case 1 counts as a trace without events, as it would in XES
+        assert_eq!(event_log.case_ids(), vec!["1", "2"]);
+        assert_eq!(event_log.activity_traces(), vec![vec![], vec!["A"]]);
+    }
+
+    #[test]
+    fn test_parse_csv_event_log_keeps_case_ids_and_attributes() {
+        let content = "\
+case:concept:name,concept:name,time:timestamp,org:resource
+7,A,2024-01-01T10:00:00+00:00,Alice
+7,B,2024-01-01T11:00:00+00:00,
+";
+        let event_log = parse_csv_event_log(
+            InputSource::Bytes(content.as_bytes()),
+            &CsvImportOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(event_log.case_ids(), vec!["7"]);
+
+        let events = &event_log.cases[0].events;
+        assert_eq!(events[0].resource(), Some("Alice"));
+        assert_eq!(events[1].resource(), None);
+        assert_eq!(
+            events[1].timestamp.unwrap().to_rfc3339(),
+            "2024-01-01T11:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_parse_csv_lifecycle_column() {
+        let content = "\
+case:concept:name,concept:name,time:timestamp,lifecycle:transition
+1,A,2024-01-01T10:00:00+00:00,start
+1,A,2024-01-01T11:00:00+00:00,complete
+1,B,2024-01-01T12:00:00+00:00,complete
+2,A,2024-01-01T10:00:00+00:00,
+";
+        let event_log = parse_csv_event_log(
+            InputSource::Bytes(content.as_bytes()),
+            &CsvImportOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(event_log.cases[0].events[0].lifecycle.as_deref(), Some("start"));
+        assert_eq!(event_log.cases[1].events[0].lifecycle, None);
+        assert_eq!(event_log.activity_traces(), vec![vec!["A", "B"], vec!["A"]]);
+        assert_eq!(event_log.activity_instances()[0][0].duration().num_hours(), 1);
+
+        let options = CsvImportOptions {
+            lifecycle_column: Some("transition".to_string()),
+            ..CsvImportOptions::default()
+        };
+        assert!(matches!(
+            parse_csv_event_log(InputSource::Bytes(content.as_bytes()), &options),
+            Err(Error::Parse(ParseError::Csv(CsvParseError::MissingColumn(_))))
+        ));
+    }
+
+    #[test]
+    fn test_parse_csv_custom_columns_delimiter_and_format() {
+        let content = "\
+id;step;when
+x;second;02.01.2024 08:00
+x;first;01.01.2024 08:00
+";
+        let options = CsvImportOptions {
+            case_column: "id".to_string(),
+            activity_column: "step".to_string(),
+            timestamp_column: "when".to_string(),
+            lifecycle_column: None,
+            delimiter: b';',
+            timestamp_format: Some("%d.%m.%Y %H:%M".to_string()),
+        };
+        let traces =
+            parse_csv_into_traces(InputSource::Bytes(content.as_bytes()), &options).unwrap();
+        assert_eq!(traces, vec![vec!["first", "second"]]);
+    }
+
+    #[test]
+    fn test_parse_csv_errors() {
+        let missing = parse_csv_into_traces(
+            InputSource::Bytes("case,activity\n1,A\n".as_bytes()),
+            &CsvImportOptions::default(),
+        );
+        assert!(matches!(
+            missing,
+            Err(Error::Parse(ParseError::Csv(CsvParseError::MissingColumn(
+                _
+            ))))
+        ));
+
+        let invalid = parse_csv_into_traces(
+            InputSource::Bytes(
+                "case:concept:name,concept:name,time:timestamp\n1,A,yesterday\n".as_bytes(),
+            ),
+            &CsvImportOptions::default(),
+        );
+        assert!(matches!(
+            invalid,
+            Err(Error::Parse(ParseError::Csv(
+                CsvParseError::InvalidTimestamp { line: 2, .. }
+            )))
+        ));
+    }
+}
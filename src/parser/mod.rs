@@ -0,0 +1,629 @@
+mod csv;
+mod mxml;
+mod ocel;
+
+pub use self::csv::{parse_csv_event_log, parse_csv_into_traces, CsvImportOptions, CsvParseError};
+pub use mxml::{parse_mxml_event_log, parse_mxml_into_traces};
+pub use ocel::{flatten_ocel, ocel_object_types, parse_ocel, parse_ocel_event_logs};
+
+use crate::error::Error;
+use crate::event_log::{
+    is_complete, ActivityInstance, Attributes, Case, Event, EventLog, TraceView, VariantLog,
+};
+use crate::input::InputSource;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub use process_mining::event_log::event_log_struct::EventLogClassifier;
+
+use process_mining::{
+    event_log::{
+        import_xes::{import_xes, XESParseError},
+        stream_xes::XESParsingTraceStream,
+        AttributeValue, Trace,
+    },
+    stream_xes_from_path, stream_xes_slice, EventLog as XesEventLog, XESImportOptions,
+};
+
+/// Errors in the content of an event log.
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+    #[error(transparent)]
+    Xes(#[from] XESParseError),
+    #[error(transparent)]
+    Csv(#[from] CsvParseError),
+    #[error("Invalid MXML: {0}")]
+    Mxml(#[from] quick_xml::Error),
+    #[error("Invalid MXML timestamp '{0}'")]
+    InvalidMxmlTimestamp(String),
+    #[error("Invalid OCEL JSON: {0}")]
+    OcelJson(#[from] serde_json::Error),
+    #[error("Invalid OCEL 2.0 log: {0}")]
+    InvalidOcel(String),
+    #[error("Classifier '{0}' is not declared in the log header")]
+    UnknownClassifier(String),
+    #[error("Object type '{0}' is not declared in the OCEL log")]
+    UnknownObjectType(String),
+}
+
+/// Options controlling which XES attributes make up the activity traces.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParseOptions {
+    /// Event attribute keys forming the activity label. Several keys act like an
+    /// XES classifier: their values are joined with `+` (e.g. `A+complete`).
+    pub activity_keys: Vec<String>,
+    /// Name of a classifier declared in the log header (`<classifier name=... keys=.../>`).
+    /// If set, its keys replace `activity_keys`.
+    pub classifier: Option<String>,
+    /// Event attribute key holding the timestamp used to order events.
+    pub timestamp_key: String,
+    /// Keep events that have no timestamp instead of dropping them. They stay
+    /// in document order, directly after the preceding event of the trace.
+    pub keep_events_without_timestamp: bool,
+    /// Trace attribute key holding the case id. Traces without it are numbered
+    /// by their position in the log.
+    pub case_key: String,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            activity_keys: vec!["concept:name".to_string()],
+            classifier: None,
+            timestamp_key: "time:timestamp".to_string(),
+            keep_events_without_timestamp: false,
+            case_key: "concept:name".to_string(),
+        }
+    }
+}
+
+/// String representation of an attribute value used in activity labels.
+fn attribute_value_to_string(value: &AttributeValue) -> Option<String> {
+    match value {
+        AttributeValue::String(value) => Some(value.clone()),
+        AttributeValue::Int(value) => Some(value.to_string()),
+        AttributeValue::Float(value) => Some(value.to_string()),
+        AttributeValue::Boolean(value) => Some(value.to_string()),
+        AttributeValue::ID(value) => Some(value.to_string()),
+        AttributeValue::Date(value) => Some(value.to_rfc3339()),
+        _ => None,
+    }
+}
+
+// Helper function to extract relevant attributes
+fn extract_event_attributes(
+    attributes: &[process_mining::event_log::Attribute],
+    options: &ParseOptions,
+) -> (Option<String>, Option<DateTime<Utc>>) {
+    let values: Vec<Option<String>> = options
+        .activity_keys
+        .iter()
+        .map(|key| {
+            attributes
+                .iter()
+                .find(|attribute| &attribute.key == key)
+                .and_then(|attribute| attribute_value_to_string(&attribute.value))
+        })
+        .collect();
+
+    // Events lacking every activity key have no activity at all
+    let name = if values.iter().all(Option::is_none) {
+        None
+    } else {
+        Some(
+            values
+                .into_iter()
+                .map(Option::unwrap_or_default)
+                .collect::<Vec<_>>()
+                .join("+"),
+        )
+    };
+
+    let date = attributes
+        .iter()
+        .find(|attribute| attribute.key == options.timestamp_key)
+        .and_then(|attribute| match &attribute.value {
+            AttributeValue::Date(value) => Some(value.with_timezone(&Utc)),
+            _ => None,
+        });
+
+    (name, date)
+}
+
+fn attributes_to_map(attributes: &[process_mining::event_log::Attribute]) -> Attributes {
+    attributes
+        .iter()
+        .filter_map(|attribute| {
+            attribute_value_to_string(&attribute.value).map(|value| (attribute.key.clone(), value))
+        })
+        .collect()
+}
+
+/// Lists the classifiers declared in the header of an XES log.
+///
+/// Only the log header is read, the traces are not parsed.
+pub fn list_classifiers(source: InputSource) -> Result<Vec<EventLogClassifier>, Error> {
+    let log_data = match source {
+        InputSource::Path(path) => {
+            // Open the file first, so that a missing file is reported as IO error
+            std::fs::File::open(&path)?;
+            stream_xes_from_path(path, XESImportOptions::default())
+                .map_err(ParseError::from)?
+                .1
+        }
+        source => {
+            let bytes = source.read_to_end()?;
+            let (_, log_data) =
+                stream_xes_slice(&bytes, XESImportOptions::default()).map_err(ParseError::from)?;
+            log_data
+        }
+    };
+    Ok(log_data.classifiers)
+}
+
+pub fn parse_into_traces(source: InputSource) -> Result<Vec<Vec<String>>, Error> {
+    parse_into_traces_with_options(source, &ParseOptions::default())
+}
+
+/// Imports the XES log and resolves the selected classifier into activity keys.
+fn import_event_log(
+    source: InputSource,
+    options: &ParseOptions,
+) -> Result<(XesEventLog, ParseOptions), Error> {
+    let event_log = import_xes(source.into_buf_read()?, XESImportOptions::default())
+        .map_err(ParseError::from)?;
+    let options = resolve_classifier(
+        event_log.classifiers.as_deref().unwrap_or_default(),
+        options,
+    )?;
+
+    Ok((event_log, options))
+}
+
+/// Replaces the activity keys by those of the selected classifier, if any.
+fn resolve_classifier(
+    classifiers: &[EventLogClassifier],
+    options: &ParseOptions,
+) -> Result<ParseOptions, ParseError> {
+    match &options.classifier {
+        Some(name) => {
+            let classifier = classifiers
+                .iter()
+                .find(|classifier| &classifier.name == name)
+                .ok_or_else(|| ParseError::UnknownClassifier(name.clone()))?;
+            Ok(ParseOptions {
+                activity_keys: classifier.keys.clone(),
+                ..options.clone()
+            })
+        }
+        None => Ok(options.clone()),
+    }
+}
+
+/// Parses an XES log into the typed [`EventLog`] model.
+///
+/// Events lacking all activity keys are dropped, as are events without timestamp
+/// unless `keep_events_without_timestamp` is set. The events of each case are
+/// ordered by timestamp, events with equal timestamps in document order (see
+/// [`EventLog::order_ties`] for other tie policies); kept untimed events stay behind
+/// their predecessor.
+///
+/// Fails with [`Error::EmptyLog`] if no event is left.
+pub fn parse_event_log(source: InputSource, options: &ParseOptions) -> Result<EventLog, Error> {
+    let (event_log, options) = import_event_log(source, options)?;
+    parse_imported_event_log(event_log, &options).non_empty()
+}
+
+/// Converts an imported XES log with resolved activity keys into an [`EventLog`].
+fn parse_imported_event_log(event_log: XesEventLog, options: &ParseOptions) -> EventLog {
+    let cases = event_log
+        .traces
+        .into_iter()
+        .enumerate()
+        .map(|(index, trace)| convert_trace(index, trace, options))
+        .collect();
+
+    EventLog { cases }
+}
+
+/// Converts the `index`-th XES trace into a [`Case`] (see [`parse_event_log`]).
+fn convert_trace(index: usize, trace: Trace, options: &ParseOptions) -> Case {
+    let attributes = attributes_to_map(&trace.attributes);
+    let id = attributes
+        .get(&options.case_key)
+        .cloned()
+        .unwrap_or_else(|| index.to_string());
+
+    let mut events: Vec<(Option<DateTime<Utc>>, Event)> = Vec::new();
+    let mut last_date = None;
+    let mut dropped_complete = false;
+    for event in trace.events {
+        let (name, date) = extract_event_attributes(&event.attributes, options);
+        let sort_date = match (&name, date) {
+            (Some(_), Some(date)) => {
+                last_date = Some(date);
+                last_date
+            }
+            // Sorts right behind the preceding event, keeping document order
+            (Some(_), None) if options.keep_events_without_timestamp => last_date,
+            _ => {
+                let transition = event
+                    .attributes
+                    .iter()
+                    .find(|attribute| attribute.key == "lifecycle:transition")
+                    .and_then(|attribute| attribute_value_to_string(&attribute.value));
+                dropped_complete |= is_complete(transition.as_deref());
+                continue;
+            }
+        };
+        let attributes = attributes_to_map(&event.attributes);
+        events.push((
+            sort_date,
+            Event {
+                activity: name.unwrap_or_default(),
+                timestamp: date,
+                lifecycle: attributes.get("lifecycle:transition").cloned(),
+                attributes,
+            },
+        ));
+    }
+    events.sort_by_key(|(date, _)| *date); // stable, keeps document order for equal timestamps
+
+    Case {
+        id,
+        attributes,
+        events: events.into_iter().map(|(_, event)| event).collect(),
+        dropped_complete,
+    }
+}
+
+/// Streams an XES log trace by trace into its [`VariantLog`].
+///
+/// Unlike [`parse_event_log`], neither the XES log nor the cases are kept in
+/// memory: each trace is reduced to its activity sequence (with the same options
+/// and filters as [`parse_into_traces_with_options`]) and then dropped, so memory
+/// grows with the number of distinct variants instead of the number of events.
+/// Case ids and attributes are therefore not available.
+pub fn parse_xes_variants(
+    source: InputSource,
+    options: &ParseOptions,
+) -> Result<VariantLog, Error> {
+    let reader = quick_xml::Reader::from_reader(source.into_buf_read()?);
+    let (mut trace_stream, log_data) =
+        XESParsingTraceStream::try_new(Box::new(reader), XESImportOptions::default())
+            .map_err(ParseError::from)?;
+    let options = resolve_classifier(&log_data.classifiers, options)?;
+
+    let mut variant_log = VariantLog::new();
+    let mut event_count = 0;
+    for (index, trace) in (&mut trace_stream).enumerate() {
+        let case = convert_trace(index, trace, &options);
+        event_count += case.events.len();
+        variant_log.add_trace(case.activity_trace());
+    }
+    if let Some(error) = trace_stream.error {
+        return Err(ParseError::from(error).into());
+    }
+
+    if event_count == 0 {
+        Err(Error::EmptyLog)
+    } else {
+        Ok(variant_log)
+    }
+}
+
+/// Like [`parse_into_traces`], but with configurable activity and timestamp keys.
+pub fn parse_into_traces_with_options(
+    source: InputSource,
+    options: &ParseOptions,
+) -> Result<Vec<Vec<String>>, Error> {
+    let event_log = parse_event_log(source, options)?;
+    Ok(event_log
+        .activity_traces()
+        .into_iter()
+        .map(|trace| trace.into_iter().map(str::to_string).collect())
+        .collect())
+}
+
+/// Parses an XES log into activity instances by pairing the `start` and `complete`
+/// lifecycle transitions of each activity (see [`ActivityInstance`]).
+///
+/// Unlike [`parse_into_traces`], `start` events are kept, so the instances carry
+/// durations and overlapping (concurrent) executions can be told apart from
+/// sequential ones. `lifecycle:transition` is never part of the activity label
+/// here, and events without a timestamp are always dropped.
+pub fn parse_into_activity_instances(
+    source: InputSource,
+    options: &ParseOptions,
+) -> Result<Vec<Vec<ActivityInstance>>, Error> {
+    Ok(parse_lifecycle_event_log(source, options)?.activity_instances())
+}
+
+/// The [`EventLog`] that [`parse_into_activity_instances`] pairs the lifecycle
+/// transitions of.
+pub(crate) fn parse_lifecycle_event_log(
+    source: InputSource,
+    options: &ParseOptions,
+) -> Result<EventLog, Error> {
+    let (event_log, mut options) = import_event_log(source, options)?;
+    options
+        .activity_keys
+        .retain(|key| key != "lifecycle:transition");
+
+    parse_imported_event_log(event_log, &options).non_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG_WITH_CUSTOM_KEYS: &str = r#"<log xes.version="1.0" xmlns="http://www.xes-standard.org/">
+<trace>
+<event>
+<string key="concept:name" value="a"/>
+<string key="org:role" value="clerk"/>
+<string key="lifecycle:transition" value="complete"/>
+<date key="time:timestamp" value="1970-01-01T00:00:02+00:00"/>
+</event>
+<event>
+<string key="concept:name" value="b"/>
+<string key="org:role" value="manager"/>
+<string key="lifecycle:transition" value="complete"/>
+</event>
+<event>
+<string key="concept:name" value="c"/>
+<string key="org:role" value="clerk"/>
+<string key="lifecycle:transition" value="complete"/>
+<date key="time:timestamp" value="1970-01-01T00:00:01+00:00"/>
+</event>
+</trace>
+</log>"#;
+
+    #[test]
+    fn test_parse_default_keys_drops_events_without_timestamp() {
+        let traces =
+            parse_into_traces(InputSource::Bytes(LOG_WITH_CUSTOM_KEYS.as_bytes())).unwrap();
+        assert_eq!(traces, vec![vec!["c", "a"]]);
+    }
+
+    #[test]
+    fn test_parse_dropped_complete_event_keeps_complete_filter() {
+        // The only complete event has no timestamp: the start events still do not count
+        let content = r#"<log xes.version="1.0" xmlns="http://www.xes-standard.org/">
+<trace>
+<event>
+<string key="concept:name" value="a"/>
+<string key="lifecycle:transition" value="start"/>
+<date key="time:timestamp" value="1970-01-01T00:00:01+00:00"/>
+</event>
+<event>
+<string key="concept:name" value="a"/>
+<string key="lifecycle:transition" value="complete"/>
+</event>
+</trace>
+<trace>
+<event>
+<string key="concept:name" value="b"/>
+<string key="lifecycle:transition" value="start"/>
+<date key="time:timestamp" value="1970-01-01T00:00:01+00:00"/>
+</event>
+</trace>
+</log>"#;
+        let traces = parse_into_traces(InputSource::Bytes(content.as_bytes())).unwrap();
+        assert_eq!(traces, vec![vec![], vec!["b"]]);
+    }
+
+    #[test]
+    fn test_parse_custom_activity_key_keeping_untimed_events() {
+        let options = ParseOptions {
+            activity_keys: vec!["org:role".to_string()],
+            keep_events_without_timestamp: true,
+            ..ParseOptions::default()
+        };
+        let traces = parse_into_traces_with_options(
+            InputSource::Bytes(LOG_WITH_CUSTOM_KEYS.as_bytes()),
+            &options,
+        )
+        .unwrap();
+        // "b" has no timestamp and stays directly behind "a"
+        assert_eq!(traces, vec![vec!["clerk", "clerk", "manager"]]);
+    }
+
+    #[test]
+    fn test_parse_event_log_keeps_cases_and_attributes() {
+        let content = r#"<log xes.version="1.0" xmlns="http://www.xes-standard.org/">
+<trace>
+<string key="concept:name" value="case-1"/>
+<event><string key="concept:name" value="b"/><string key="org:resource" value="Bob"/><date key="time:timestamp" value="1970-01-01T00:00:02+00:00"/></event>
+<event><string key="concept:name" value="a"/><string key="org:resource" value="Alice"/><date key="time:timestamp" value="1970-01-01T00:00:01+00:00"/></event>
+</trace>
+<trace>
+<event><string key="concept:name" value="a"/><date key="time:timestamp" value="1970-01-01T00:00:01+00:00"/></event>
+</trace>
+</log>"#;
+        let event_log = parse_event_log(
+            InputSource::Bytes(content.as_bytes()),
+            &ParseOptions::default(),
+        )
+        .unwrap();
+
+        // The second trace has no case id and is numbered by its position
+        assert_eq!(event_log.case_ids(), vec!["case-1", "1"]);
+        let first = &event_log.cases[0];
+        assert_eq!(first.attributes["concept:name"], "case-1");
+        assert_eq!(first.activity_trace(), vec!["a", "b"]);
+        assert_eq!(first.events[0].resource(), Some("Alice"));
+        assert_eq!(event_log.cases_containing(&["b"]), vec!["case-1"]);
+    }
+
+    const LOG_WITH_CLASSIFIERS: &str = r#"<log xes.version="1.0" xmlns="http://www.xes-standard.org/">
+<classifier name="Activity" keys="concept:name"/>
+<classifier name="Activity and Role" keys="concept:name org:role"/>
+<trace>
+<event>
+<string key="concept:name" value="a"/>
+<string key="org:role" value="clerk"/>
+<date key="time:timestamp" value="1970-01-01T00:00:01+00:00"/>
+</event>
+<event>
+<string key="concept:name" value="b"/>
+<string key="org:role" value="manager"/>
+<date key="time:timestamp" value="1970-01-01T00:00:02+00:00"/>
+</event>
+</trace>
+</log>"#;
+
+    #[test]
+    fn test_list_classifiers() {
+        let classifiers =
+            list_classifiers(InputSource::Bytes(LOG_WITH_CLASSIFIERS.as_bytes())).unwrap();
+        let names: Vec<&str> = classifiers.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Activity", "Activity and Role"]);
+        assert_eq!(classifiers[1].keys, vec!["concept:name", "org:role"]);
+    }
+
+    #[test]
+    fn test_parse_with_declared_classifier() {
+        let options = ParseOptions {
+            classifier: Some("Activity and Role".to_string()),
+            ..ParseOptions::default()
+        };
+        let traces = parse_into_traces_with_options(
+            InputSource::Bytes(LOG_WITH_CLASSIFIERS.as_bytes()),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(traces, vec![vec!["a+clerk", "b+manager"]]);
+
+        let unknown = ParseOptions {
+            classifier: Some("Resource".to_string()),
+            ..ParseOptions::default()
+        };
+        assert!(matches!(
+            parse_into_traces_with_options(
+                InputSource::Bytes(LOG_WITH_CLASSIFIERS.as_bytes()),
+                &unknown
+            ),
+            Err(Error::Parse(ParseError::UnknownClassifier(_)))
+        ));
+    }
+
+    #[test]
+    fn test_parse_gzip_compressed_log() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(LOG_WITH_CLASSIFIERS.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let traces = parse_into_traces(InputSource::Bytes(&compressed)).unwrap();
+        assert_eq!(traces, vec![vec!["a", "b"]]);
+        assert_eq!(
+            list_classifiers(InputSource::Bytes(&compressed))
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_parse_xes_variants_matches_parse_into_traces() {
+        let options = ParseOptions {
+            classifier: Some("Activity and Role".to_string()),
+            ..ParseOptions::default()
+        };
+        let content = LOG_WITH_CLASSIFIERS.replace("</log>", "")
+            + &LOG_WITH_CLASSIFIERS[LOG_WITH_CLASSIFIERS.find("<trace>").unwrap()..];
+        let traces =
+            parse_into_traces_with_options(InputSource::Bytes(content.as_bytes()), &options)
+                .unwrap();
+        let variant_log =
+            parse_xes_variants(InputSource::Bytes(content.as_bytes()), &options).unwrap();
+
+        assert_eq!(traces.len(), 2);
+        assert_eq!(variant_log.trace_count(), 2);
+        assert_eq!(variant_log.variant_count(), 1);
+        assert_eq!(variant_log.activity_traces(), traces);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let missing = parse_into_traces(InputSource::path("does/not/exist.xes"));
+        assert!(matches!(missing, Err(Error::Io(_))));
+
+        let no_events = r#"<log xes.version="1.0" xmlns="http://www.xes-standard.org/">
+<trace><event><string key="concept:name" value="a"/></event></trace>
+</log>"#;
+        let empty = parse_into_traces(InputSource::Bytes(no_events.as_bytes()));
+        assert!(matches!(empty, Err(Error::EmptyLog)));
+
+        let reader = InputSource::Reader(Box::new(LOG_WITH_CUSTOM_KEYS.as_bytes()));
+        assert_eq!(parse_into_traces(reader).unwrap(), vec![vec!["c", "a"]]);
+    }
+
+    #[test]
+    fn test_parse_into_activity_instances() {
+        let content = r#"<log xes.version="1.0" xmlns="http://www.xes-standard.org/">
+<trace>
+<event><string key="concept:name" value="a"/><string key="lifecycle:transition" value="start"/><date key="time:timestamp" value="1970-01-01T00:00:01+00:00"/></event>
+<event><string key="concept:name" value="b"/><string key="lifecycle:transition" value="start"/><date key="time:timestamp" value="1970-01-01T00:00:02+00:00"/></event>
+<event><string key="concept:name" value="a"/><string key="lifecycle:transition" value="complete"/><date key="time:timestamp" value="1970-01-01T00:00:03+00:00"/></event>
+<event><string key="concept:name" value="b"/><string key="lifecycle:transition" value="complete"/><date key="time:timestamp" value="1970-01-01T00:00:04+00:00"/></event>
+<event><string key="concept:name" value="c"/><string key="lifecycle:transition" value="complete"/><date key="time:timestamp" value="1970-01-01T00:00:05+00:00"/></event>
+</trace>
+</log>"#;
+        // Complete-only parsing loses the information that a and b overlap
+        let traces = parse_into_traces(InputSource::Bytes(content.as_bytes())).unwrap();
+        assert_eq!(traces, vec![vec!["a", "b", "c"]]);
+
+        let instances = parse_into_activity_instances(
+            InputSource::Bytes(content.as_bytes()),
+            &ParseOptions::default(),
+        )
+        .unwrap();
+        let durations: Vec<(&str, i64)> = instances[0]
+            .iter()
+            .map(|i| (i.activity.as_str(), i.duration().num_seconds()))
+            .collect();
+        assert_eq!(durations, vec![("a", 2), ("b", 2), ("c", 0)]);
+        assert!(instances[0][0].overlaps(&instances[0][1]));
+    }
+
+    #[test]
+    fn test_parse_combined_activity_keys() {
+        let options = ParseOptions {
+            activity_keys: vec![
+                "concept:name".to_string(),
+                "lifecycle:transition".to_string(),
+            ],
+            ..ParseOptions::default()
+        };
+        let traces = parse_into_traces_with_options(
+            InputSource::Bytes(LOG_WITH_CUSTOM_KEYS.as_bytes()),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(traces, vec![vec!["c+complete", "a+complete"]]);
+    }
+
+    #[test]
+    fn test_variants_of_traces() {
+        let traces = vec![
+            vec!["A", "B", "C"],
+            vec!["A", "B", "C"],
+            vec!["B", "C", "D"],
+            vec!["A", "B", "C"],
+            vec!["B", "C", "D"],
+            vec!["E", "F", "G"],
+        ];
+
+        let result = traces.trace_variants();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0], (vec!["A", "B", "C"], 3));
+        assert_eq!(result[1], (vec!["B", "C", "D"], 2));
+        assert_eq!(result[2], (vec!["E", "F", "G"], 1));
+    }
+}
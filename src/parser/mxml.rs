@@ -0,0 +1,239 @@
+use super::ParseError;
+use crate::error::Error;
+use crate::event_log::{is_complete, Attributes, Case, Event, EventLog, TraceView};
+use crate::input::InputSource;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use quick_xml::events::{BytesStart, Event as XmlEvent};
+use quick_xml::Reader;
+
+/// Element of an `AuditTrailEntry` (or `Data` attribute) whose text is being read.
+enum Field {
+    WorkflowModelElement,
+    EventType,
+    Timestamp,
+    Originator,
+    Attribute(String),
+}
+
+#[derive(Default)]
+struct AuditTrailEntry {
+    workflow_model_element: Option<String>,
+    event_type: Option<String>,
+    timestamp: Option<String>,
+    attributes: Attributes,
+}
+
+/// Parses an MXML timestamp (`xs:dateTime`, with or without time zone).
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|date| date.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+                .ok()
+                .map(|date| date.and_utc())
+        })
+}
+
+fn attribute_value(element: &BytesStart, name: &str) -> Result<Option<String>, ParseError> {
+    let value = element
+        .try_get_attribute(name)?
+        .map(|attribute| attribute.unescape_value().map(|value| value.into_owned()))
+        .transpose()?;
+    Ok(value)
+}
+
+/// Reads a ProM MXML log into the typed [`EventLog`] model.
+///
+/// Every `ProcessInstance` becomes a case, every `AuditTrailEntry` an event with
+/// `WorkflowModelElement` as activity, `EventType` (in lower case, like the XES
+/// lifecycle transitions) as lifecycle transition and `Timestamp` as timestamp. The
+/// `Originator` is stored as `org:resource` and the `Data` attributes are kept under
+/// their names. Timestamps are optional in MXML,
+/// so entries without one are kept behind their predecessor in document order.
+/// Process instances of all `Process` elements are read.
+pub fn parse_mxml_event_log(source: InputSource) -> Result<EventLog, Error> {
+    let mut reader = Reader::from_reader(source.into_buf_read()?);
+    reader.trim_text(true);
+
+    let mut cases: Vec<Case> = Vec::new();
+    let mut case: Option<Case> = None;
+    let mut entry: Option<AuditTrailEntry> = None;
+    let mut field: Option<Field> = None;
+    let mut text = String::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf).map_err(ParseError::from)? {
+            XmlEvent::Start(element) => match element.local_name().as_ref() {
+                b"ProcessInstance" => {
+                    let id =
+                        attribute_value(&element, "id")?.unwrap_or_else(|| cases.len().to_string());
+                    case = Some(Case {
+                        id,
+                        ..Case::default()
+                    });
+                }
+                b"AuditTrailEntry" => entry = Some(AuditTrailEntry::default()),
+                b"WorkflowModelElement" => field = Some(Field::WorkflowModelElement),
+                b"EventType" => field = Some(Field::EventType),
+                b"Timestamp" => field = Some(Field::Timestamp),
+                b"Originator" => field = Some(Field::Originator),
+                b"Attribute" => {
+                    field = attribute_value(&element, "name")?.map(Field::Attribute);
+                }
+                _ => {}
+            },
+            XmlEvent::Text(value) if field.is_some() => {
+                text.push_str(&value.unescape().map_err(ParseError::from)?);
+            }
+            XmlEvent::CData(value) if field.is_some() => {
+                text.push_str(&String::from_utf8_lossy(&value));
+            }
+            XmlEvent::End(element) => match element.local_name().as_ref() {
+                b"WorkflowModelElement"
+                | b"EventType"
+                | b"Timestamp"
+                | b"Originator"
+                | b"Attribute" => {
+                    let value = std::mem::take(&mut text);
+                    match (field.take(), entry.as_mut(), case.as_mut()) {
+                        (Some(Field::WorkflowModelElement), Some(entry), _) => {
+                            entry.workflow_model_element = Some(value);
+                        }
+                        (Some(Field::EventType), Some(entry), _) => {
+                            entry.event_type = Some(value.to_lowercase());
+                        }
+                        (Some(Field::Timestamp), Some(entry), _) => entry.timestamp = Some(value),
+                        (Some(Field::Originator), Some(entry), _) => {
+                            entry.attributes.insert("org:resource".to_string(), value);
+                        }
+                        (Some(Field::Attribute(name)), Some(entry), _) => {
+                            entry.attributes.insert(name, value);
+                        }
+                        (Some(Field::Attribute(name)), None, Some(case)) => {
+                            case.attributes.insert(name, value);
+                        }
+                        _ => {}
+                    }
+                }
+                b"AuditTrailEntry" => {
+                    if let (Some(entry), Some(case)) = (entry.take(), case.as_mut()) {
+                        let timestamp = match entry.timestamp {
+                            Some(value) => Some(
+                                parse_timestamp(&value)
+                                    .ok_or(ParseError::InvalidMxmlTimestamp(value))?,
+                            ),
+                            None => None,
+                        };
+                        if let Some(activity) = entry.workflow_model_element {
+                            case.events.push(Event {
+                                activity,
+                                timestamp,
+                                lifecycle: entry.event_type,
+                                attributes: entry.attributes,
+                            });
+                        } else {
+                            case.dropped_complete |= is_complete(entry.event_type.as_deref());
+                        }
+                    }
+                }
+                b"ProcessInstance" => cases.extend(case.take()),
+                _ => {}
+            },
+            XmlEvent::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    for case in &mut cases {
+        // Untimed entries sort right behind the preceding entry
+        let mut last_date = None;
+        let mut events: Vec<(Option<DateTime<Utc>>, Event)> = std::mem::take(&mut case.events)
+            .into_iter()
+            .map(|event| {
+                last_date = event.timestamp.or(last_date);
+                (last_date, event)
+            })
+            .collect();
+        events.sort_by_key(|(date, _)| *date); // stable, keeps document order for equal timestamps
+        case.events = events.into_iter().map(|(_, event)| event).collect();
+    }
+
+    EventLog { cases }.non_empty()
+}
+
+/// Reads an MXML log into activity traces, like [`super::parse_into_traces`] does for XES.
+pub fn parse_mxml_into_traces(source: InputSource) -> Result<Vec<Vec<String>>, Error> {
+    let event_log = parse_mxml_event_log(source)?;
+    Ok(event_log
+        .activity_traces()
+        .into_iter()
+        .map(|trace| trace.into_iter().map(str::to_string).collect())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MXML_LOG: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<WorkflowLog>
+<Source program="test"/>
+<Process id="order">
+<ProcessInstance id="case-1">
+<Data><Attribute name="priority">high</Attribute></Data>
+<AuditTrailEntry>
+<WorkflowModelElement>B</WorkflowModelElement>
+<EventType>complete</EventType>
+<Timestamp>2006-01-01T00:00:02.000+01:00</Timestamp>
+<Originator>Bob</Originator>
+</AuditTrailEntry>
+<AuditTrailEntry>
+<WorkflowModelElement>A</WorkflowModelElement>
+<EventType>start</EventType>
+<Timestamp>2006-01-01T00:00:00.000+01:00</Timestamp>
+</AuditTrailEntry>
+<AuditTrailEntry>
+<WorkflowModelElement>A</WorkflowModelElement>
+<EventType>complete</EventType>
+<Timestamp>2006-01-01T00:00:01.000+01:00</Timestamp>
+</AuditTrailEntry>
+</ProcessInstance>
+<ProcessInstance id="case-2">
+<AuditTrailEntry><WorkflowModelElement>A</WorkflowModelElement><EventType>complete</EventType></AuditTrailEntry>
+<AuditTrailEntry><WorkflowModelElement>C &amp; D</WorkflowModelElement><EventType>complete</EventType></AuditTrailEntry>
+</ProcessInstance>
+</Process>
+</WorkflowLog>"#;
+
+    #[test]
+    fn test_parse_mxml_into_traces() {
+        let traces = parse_mxml_into_traces(InputSource::Bytes(MXML_LOG.as_bytes())).unwrap();
+        // Only complete events are used, untimed entries keep their document order
+        assert_eq!(traces, vec![vec!["A", "B"], vec!["A", "C & D"]]);
+    }
+
+    #[test]
+    fn test_parse_mxml_event_log() {
+        let event_log = parse_mxml_event_log(InputSource::Bytes(MXML_LOG.as_bytes())).unwrap();
+        assert_eq!(event_log.case_ids(), vec!["case-1", "case-2"]);
+
+        let case = &event_log.cases[0];
+        assert_eq!(case.attributes["priority"], "high");
+        assert_eq!(case.events[0].lifecycle.as_deref(), Some("start"));
+        assert_eq!(case.events[2].resource(), Some("Bob"));
+        assert_eq!(
+            case.events[2].timestamp.unwrap().to_rfc3339(),
+            "2005-12-31T23:00:02+00:00"
+        );
+        assert_eq!(case.activity_instances().len(), 2);
+
+        let invalid = MXML_LOG.replace("2006-01-01T00:00:02.000+01:00", "yesterday");
+        assert!(matches!(
+            parse_mxml_event_log(InputSource::Bytes(invalid.as_bytes())),
+            Err(Error::Parse(ParseError::InvalidMxmlTimestamp(_)))
+        ));
+    }
+}
@@ -0,0 +1,509 @@
+use super::ParseError;
+use crate::error::Error;
+use crate::event_log::{Attributes, Case, Event, EventLog};
+use crate::input::InputSource;
+use chrono::Utc;
+use process_mining::event_log::ocel::xml_ocel_import::{
+    import_ocel_xml_slice, parse_date, OCELImportOptions,
+};
+use process_mining::OCEL;
+use quick_xml::events::{BytesStart, Event as XmlEvent};
+use std::collections::{HashMap, HashSet};
+
+/// Reads an OCEL 2.0 object-centric event log in its JSON or XML serialization.
+///
+/// The format is detected from the content (JSON documents start with `{`). XML
+/// documents are checked to be OCEL 2.0 logs the importer of `process_mining` can
+/// read before they are imported. Logs without events or objects are rejected.
+pub fn parse_ocel(source: InputSource) -> Result<OCEL, Error> {
+    let bytes = source.read_to_end()?;
+    let is_json = bytes
+        .iter()
+        .find(|byte| !byte.is_ascii_whitespace())
+        .is_some_and(|&byte| byte == b'{');
+
+    let ocel: OCEL = if is_json {
+        serde_json::from_slice(&bytes).map_err(ParseError::from)?
+    } else {
+        check_ocel_xml(&bytes)?;
+        import_ocel_xml_slice(&bytes)
+    };
+    if ocel.events.is_empty() || ocel.objects.is_empty() {
+        return Err(
+            ParseError::InvalidOcel("the log contains no events or objects".to_string()).into(),
+        );
+    }
+    Ok(ocel)
+}
+
+/// Section of an OCEL 2.0 XML document, following the states of the importer.
+#[derive(Clone, Copy, PartialEq)]
+enum XmlSection {
+    None,
+    Log,
+    ObjectTypes,
+    ObjectType,
+    ObjectTypeAttributes,
+    EventTypes,
+    EventType,
+    EventTypeAttributes,
+    Objects,
+    Object,
+    Events,
+    Event,
+}
+
+/// Checks that `bytes` is a well-formed OCEL 2.0 XML log whose elements carry every
+/// attribute the importer of `process_mining` relies on (it panics otherwise, which
+/// cannot be caught in the wasm build).
+fn check_ocel_xml(bytes: &[u8]) -> Result<(), ParseError> {
+    let invalid = |message: String| ParseError::InvalidOcel(message);
+    let attribute = |element: &BytesStart, key: &str| -> Result<String, ParseError> {
+        match element.try_get_attribute(key) {
+            Ok(Some(attribute)) => Ok(String::from_utf8_lossy(&attribute.value).into_owned()),
+            _ => Err(invalid(format!(
+                "<{}> lacks the attribute '{}'",
+                String::from_utf8_lossy(element.name().as_ref()),
+                key
+            ))),
+        }
+    };
+    let options = OCELImportOptions::default();
+    let time = |element: &BytesStart| -> Result<bool, ParseError> {
+        Ok(parse_date(&attribute(element, "time")?, &options).is_ok())
+    };
+
+    let mut reader = quick_xml::Reader::from_reader(bytes);
+    reader.trim_text(true);
+    let mut buf = Vec::new();
+    let mut section = XmlSection::None;
+    let mut has_root = false;
+    // Declared attributes by (object or event type, attribute name)
+    let mut object_attributes: HashSet<(String, String)> = HashSet::new();
+    let mut event_attributes: HashSet<(String, String)> = HashSet::new();
+    let mut current_type = String::new();
+    // Type and last attribute of the current object or event
+    let mut current_item: (String, Option<String>) = (String::new(), None);
+
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(|e| invalid(e.to_string()))?;
+        let (element, is_start) = match &event {
+            XmlEvent::Start(element) => (Some(element), true),
+            XmlEvent::Empty(element) => (Some(element), false),
+            _ => (None, false),
+        };
+
+        if let Some(element) = element {
+            let name = element.name();
+            match (section, name.as_ref()) {
+                (XmlSection::None, b"log") if !has_root => {
+                    let is_xes = element.attributes().flatten().any(|attribute| {
+                        String::from_utf8_lossy(&attribute.value).contains("xes-standard")
+                    });
+                    if is_xes {
+                        return Err(invalid(
+                            "this is an XES log, not an OCEL 2.0 log".to_string(),
+                        ));
+                    }
+                    has_root = true;
+                    if is_start {
+                        section = XmlSection::Log;
+                    }
+                }
+                (XmlSection::None, name) => {
+                    return Err(invalid(format!(
+                        "the root element <{}> is not an OCEL 2.0 <log>",
+                        String::from_utf8_lossy(name)
+                    )))
+                }
+                (XmlSection::Log, b"object-types" | b"event-types" | b"objects" | b"events")
+                    if !is_start => {}
+                (XmlSection::Log, b"object-types") => section = XmlSection::ObjectTypes,
+                (XmlSection::Log, b"event-types") => section = XmlSection::EventTypes,
+                (XmlSection::Log, b"objects") => section = XmlSection::Objects,
+                (XmlSection::Log, b"events") => section = XmlSection::Events,
+                (XmlSection::Log, name) => {
+                    return Err(invalid(format!(
+                        "<{}> is not an OCEL 2.0 log section",
+                        String::from_utf8_lossy(name)
+                    )))
+                }
+                (XmlSection::ObjectTypes, b"object-type") if is_start => {
+                    current_type = attribute(element, "name")?;
+                    section = XmlSection::ObjectType;
+                }
+                (XmlSection::EventTypes, b"event-type") if is_start => {
+                    current_type = attribute(element, "name")?;
+                    section = XmlSection::EventType;
+                }
+                (XmlSection::ObjectType, b"attributes") if is_start => {
+                    section = XmlSection::ObjectTypeAttributes
+                }
+                (XmlSection::EventType, b"attributes") if is_start => {
+                    section = XmlSection::EventTypeAttributes
+                }
+                (XmlSection::ObjectTypeAttributes, b"attribute") if !is_start => {
+                    attribute(element, "type")?;
+                    object_attributes.insert((current_type.clone(), attribute(element, "name")?));
+                }
+                (XmlSection::EventTypeAttributes, b"attribute") if !is_start => {
+                    attribute(element, "type")?;
+                    event_attributes.insert((current_type.clone(), attribute(element, "name")?));
+                }
+                (XmlSection::Objects, b"object") if is_start => {
+                    attribute(element, "id")?;
+                    current_item = (attribute(element, "type")?, None);
+                    section = XmlSection::Object;
+                }
+                (XmlSection::Events, b"event") if is_start => {
+                    attribute(element, "id")?;
+                    if !time(element)? {
+                        return Err(invalid(format!(
+                            "invalid event time '{}'",
+                            attribute(element, "time")?
+                        )));
+                    }
+                    current_item = (attribute(element, "type")?, None);
+                    section = XmlSection::Event;
+                }
+                (XmlSection::Object, b"attribute") => {
+                    let name = attribute(element, "name")?;
+                    // Attributes with an invalid time are skipped by the importer
+                    if time(element)? {
+                        current_item.1 = Some(name);
+                    }
+                }
+                (XmlSection::Event, b"attribute") => {
+                    current_item.1 = Some(attribute(element, "name")?);
+                }
+                (XmlSection::Object, b"relationship" | b"relobj")
+                | (XmlSection::Event, b"relationship" | b"object" | b"relobj")
+                    if !is_start =>
+                {
+                    attribute(element, "object-id")?;
+                    attribute(element, "qualifier")?;
+                }
+                _ => {}
+            }
+        }
+
+        match event {
+            XmlEvent::Text(_) if matches!(section, XmlSection::Object | XmlSection::Event) => {
+                let declared = match section {
+                    XmlSection::Object => &object_attributes,
+                    _ => &event_attributes,
+                };
+                let (item_type, attribute) = &current_item;
+                match attribute {
+                    Some(attribute)
+                        if declared.contains(&(item_type.clone(), attribute.clone())) => {}
+                    Some(attribute) => {
+                        return Err(invalid(format!(
+                            "attribute '{}' is not declared for type '{}'",
+                            attribute, item_type
+                        )))
+                    }
+                    None => {
+                        return Err(invalid(format!(
+                            "value outside of an attribute of a '{}'",
+                            item_type
+                        )))
+                    }
+                }
+            }
+            XmlEvent::End(element) => {
+                section = match (section, element.name().as_ref()) {
+                    (XmlSection::ObjectTypeAttributes, b"attributes") => XmlSection::ObjectType,
+                    (XmlSection::ObjectType, b"object-type") => XmlSection::ObjectTypes,
+                    (XmlSection::EventTypeAttributes, b"attributes") => XmlSection::EventType,
+                    (XmlSection::EventType, b"event-type") => XmlSection::EventTypes,
+                    (XmlSection::Object, b"object") => XmlSection::Objects,
+                    (XmlSection::Event, b"event") => XmlSection::Events,
+                    (
+                        XmlSection::ObjectTypes
+                        | XmlSection::EventTypes
+                        | XmlSection::Objects
+                        | XmlSection::Events,
+                        b"object-types" | b"event-types" | b"objects" | b"events",
+                    ) => XmlSection::Log,
+                    (XmlSection::Log, b"log") => XmlSection::None,
+                    (section, _) => section,
+                };
+            }
+            XmlEvent::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if !has_root {
+        Err(invalid("the document has no <log> element".to_string()))
+    } else if section != XmlSection::None || !bytes.trim_ascii_end().ends_with(b">") {
+        // The reader also closes an unterminated "</log" at the end
+        Err(invalid("the document ends before </log>".to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Names of the object types declared in the log.
+pub fn ocel_object_types(ocel: &OCEL) -> Vec<&str> {
+    ocel.object_types.iter().map(|t| t.name.as_str()).collect()
+}
+
+/// Flattens an object-centric log on one object type.
+///
+/// Every object of the type becomes a case (named by the object id) containing all
+/// events related to the object, ordered by time. The activity of an event is its
+/// event type. An event related to several objects of the type is copied into each
+/// of their cases, as in the usual OCEL flattening. Objects without events are
+/// left out.
+pub fn flatten_ocel(ocel: &OCEL, object_type: &str) -> Result<EventLog, Error> {
+    if !ocel.object_types.iter().any(|t| t.name == object_type) {
+        return Err(ParseError::UnknownObjectType(object_type.to_string()).into());
+    }
+
+    let mut case_positions: HashMap<&str, usize> = HashMap::new();
+    let mut cases: Vec<Case> = Vec::new();
+    for object in ocel.objects.iter().filter(|o| o.object_type == object_type) {
+        let mut attributes = object.attributes.clone();
+        // Attribute values change over time, the case keeps the latest one
+        attributes.sort_by_key(|attribute| attribute.time);
+        case_positions.insert(&object.id, cases.len());
+        cases.push(Case {
+            id: object.id.clone(),
+            attributes: attributes
+                .into_iter()
+                .map(|attribute| (attribute.name, attribute.value.to_string()))
+                .collect(),
+            events: Vec::new(),
+            dropped_complete: false,
+        });
+    }
+
+    for ocel_event in &ocel.events {
+        let mut related: Vec<usize> = ocel_event
+            .relationships
+            .iter()
+            .filter_map(|relationship| case_positions.get(relationship.object_id.as_str()))
+            .copied()
+            .collect();
+        related.sort_unstable();
+        related.dedup();
+
+        for position in related {
+            let mut attributes: Attributes = ocel_event
+                .attributes
+                .iter()
+                .map(|attribute| (attribute.name.clone(), attribute.value.to_string()))
+                .collect();
+            attributes.insert("ocel:eid".to_string(), ocel_event.id.clone());
+            cases[position].events.push(Event {
+                activity: ocel_event.event_type.clone(),
+                timestamp: Some(ocel_event.time.with_timezone(&Utc)),
+                lifecycle: None,
+                attributes,
+            });
+        }
+    }
+
+    cases.retain(|case| !case.events.is_empty());
+    for case in &mut cases {
+        case.events.sort_by_key(|event| event.timestamp); // stable, keeps log order for equal times
+    }
+
+    EventLog { cases }.non_empty()
+}
+
+/// Reads an OCEL 2.0 log and flattens it on the given object type, or on every
+/// object type with at least one event if `object_type` is `None`.
+///
+/// Returns the flattened logs together with their object type, in the order the
+/// types are declared in the log.
+pub fn parse_ocel_event_logs(
+    source: InputSource,
+    object_type: Option<&str>,
+) -> Result<Vec<(String, EventLog)>, Error> {
+    let ocel = parse_ocel(source)?;
+
+    match object_type {
+        Some(object_type) => Ok(vec![(
+            object_type.to_string(),
+            flatten_ocel(&ocel, object_type)?,
+        )]),
+        None => {
+            let mut event_logs: Vec<(String, EventLog)> = Vec::new();
+            for object_type in ocel_object_types(&ocel) {
+                match flatten_ocel(&ocel, object_type) {
+                    Ok(event_log) => event_logs.push((object_type.to_string(), event_log)),
+                    // The object type has no events
+                    Err(Error::EmptyLog) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            if event_logs.is_empty() {
+                Err(Error::EmptyLog)
+            } else {
+                Ok(event_logs)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_log::TraceView;
+
+    const OCEL_JSON: &str = r#"{
+  "objectTypes": [
+    {"name": "order", "attributes": []},
+    {"name": "item", "attributes": []},
+    {"name": "invoice", "attributes": []}
+  ],
+  "eventTypes": [
+    {"name": "place order", "attributes": []},
+    {"name": "pick item", "attributes": []},
+    {"name": "ship", "attributes": []}
+  ],
+  "objects": [
+    {"id": "o1", "type": "order"},
+    {"id": "i1", "type": "item"},
+    {"id": "i2", "type": "item"},
+    {"id": "inv1", "type": "invoice"}
+  ],
+  "events": [
+    {"id": "e3", "type": "ship", "time": "2024-01-03T00:00:00Z",
+     "relationships": [{"objectId": "o1", "qualifier": ""}, {"objectId": "i1", "qualifier": ""}, {"objectId": "i2", "qualifier": ""}]},
+    {"id": "e1", "type": "place order", "time": "2024-01-01T00:00:00Z",
+     "relationships": [{"objectId": "o1", "qualifier": ""}, {"objectId": "i1", "qualifier": ""}, {"objectId": "i2", "qualifier": ""}]},
+    {"id": "e2", "type": "pick item", "time": "2024-01-02T00:00:00Z",
+     "relationships": [{"objectId": "i1", "qualifier": ""}]}
+  ]
+}"#;
+
+    const OCEL_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<log>
+<object-types><object-type name="order"><attributes/></object-type></object-types>
+<event-types>
+<event-type name="place order"><attributes/></event-type>
+<event-type name="ship"><attributes/></event-type>
+</event-types>
+<objects><object id="o1" type="order"><attributes/></object></objects>
+<events>
+<event id="e1" type="place order" time="2024-01-01T00:00:00Z"><objects><relationship object-id="o1" qualifier=""/></objects></event>
+<event id="e2" type="ship" time="2024-01-02T00:00:00Z"><objects><relationship object-id="o1" qualifier=""/></objects></event>
+</events>
+</log>"#;
+
+    #[test]
+    fn test_flatten_ocel_per_object_type() {
+        let ocel = parse_ocel(InputSource::Bytes(OCEL_JSON.as_bytes())).unwrap();
+        assert_eq!(ocel_object_types(&ocel), vec!["order", "item", "invoice"]);
+
+        let orders = flatten_ocel(&ocel, "order").unwrap();
+        assert_eq!(orders.case_ids(), vec!["o1"]);
+        assert_eq!(orders.activity_traces(), vec![vec!["place order", "ship"]]);
+
+        let items = flatten_ocel(&ocel, "item").unwrap();
+        assert_eq!(
+            items.activity_traces(),
+            vec![
+                vec!["place order", "pick item", "ship"],
+                vec!["place order", "ship"]
+            ]
+        );
+
+        assert!(matches!(
+            flatten_ocel(&ocel, "invoice"),
+            Err(Error::EmptyLog)
+        ));
+        assert!(matches!(
+            flatten_ocel(&ocel, "customer"),
+            Err(Error::Parse(ParseError::UnknownObjectType(_)))
+        ));
+    }
+
+    #[test]
+    fn test_parse_ocel_xml() {
+        let content = OCEL_XML;
+        let event_logs =
+            parse_ocel_event_logs(InputSource::Bytes(content.as_bytes()), Some("order")).unwrap();
+        assert_eq!(
+            event_logs[0].1.activity_traces(),
+            vec![vec!["place order", "ship"]]
+        );
+    }
+
+    #[test]
+    fn test_parse_ocel_rejects_invalid_xml() {
+        let order = r#"<object-types><object-type name="order"><attributes/></object-type></object-types>
+<event-types><event-type name="ship"><attributes/></event-type></event-types>
+<objects><object id="o1" type="order"><attributes/></object></objects>"#;
+        for content in [
+            // An XES log
+            r#"<log xes.version="1.0" xmlns="http://www.xes-standard.org/"><trace/></log>"#
+                .to_string(),
+            // Not a log at all
+            "<project><name>x</name></project>".to_string(),
+            // Broken XML
+            "<log><events></log>".to_string(),
+            // An event without a time
+            format!(
+                r#"<log>{}<events><event id="e1" type="ship"><objects/></event></events></log>"#,
+                order
+            ),
+            // An event with an invalid time
+            format!(
+                r#"<log>{}<events><event id="e1" type="ship" time="yesterday"><objects/></event></events></log>"#,
+                order
+            ),
+            // A value of an undeclared attribute
+            format!(
+                r#"<log>{}<events><event id="e1" type="ship" time="2024-01-01T00:00:00Z"><attributes><attribute name="weight">3</attribute></attributes></event></events></log>"#,
+                order
+            ),
+            // No events
+            format!("<log>{}<events/></log>", order),
+        ] {
+            assert!(
+                matches!(
+                    parse_ocel(InputSource::Bytes(content.as_bytes())),
+                    Err(Error::Parse(ParseError::InvalidOcel(_)))
+                ),
+                "{}",
+                content
+            );
+        }
+    }
+
+    #[test]
+    fn test_analyze_rejects_truncated_and_non_ocel_xml_files() {
+        let config = crate::AnalysisConfig::default();
+        let mut contents: Vec<&str> = (1..OCEL_XML.len())
+            .filter(|&end| OCEL_XML.is_char_boundary(end))
+            .map(|end| &OCEL_XML[..end])
+            .collect();
+        contents.push("<configuration><entry key=\"a\"/></configuration>");
+        for content in contents {
+            assert!(
+                matches!(
+                    crate::analyze("orders.xml", InputSource::Bytes(content.as_bytes()), &config),
+                    Err(Error::Parse(ParseError::InvalidOcel(_)))
+                ),
+                "{}",
+                content
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_ocel_event_logs_skips_types_without_events() {
+        let event_logs =
+            parse_ocel_event_logs(InputSource::Bytes(OCEL_JSON.as_bytes()), None).unwrap();
+        let object_types: Vec<&str> = event_logs.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(object_types, vec!["order", "item"]);
+    }
+}